@@ -5,6 +5,7 @@
 module sui::test_scenario {
     use sui::id::{Self, ID, VersionedID};
     use sui::tx_context::{Self, TxContext};
+    use sui::utf8::{Self, String};
     use std::option::{Self, Option};
     use std::vector;
 
@@ -33,6 +34,18 @@ module sui::test_scenario {
     /// Object of given ID cannot be found in the inventory.
     const EObjectIDNotFound: u64 = 6;
 
+    /// Expected a mutable shared object, but the object has a different owner kind.
+    const ENotSharedObject: u64 = 7;
+
+    /// Requested the sender of an event that has not been emitted yet.
+    const EInvalidEventIndex: u64 = 8;
+
+    /// Attempted to restore an inventory snapshot under a name that no snapshot was taken under.
+    const ESnapshotNotFound: u64 = 9;
+
+    /// Owner kind of shared objects, using the encoding of `ids_by_owner_kind`.
+    const SHARED_OWNER_KIND: u8 = 3;
+
     /// Utility for mocking a multi-transaction Sui execution in a single Move procedure.
     /// A `Scenario` maintains a view of the global object pool built up by the execution.
     /// These objects can be accessed via functions like `take_owned`, which gives the
@@ -66,6 +79,54 @@ module sui::test_scenario {
         /// The `i`th entry in this vector is the start index for events emitted by the `i`th transaction.
         /// This information allows us to partition events emitted by distinct transactions
         event_start_indexes: vector<u64>,
+        /// The `i`th entry in this vector is the sender of the `i`th transaction, and therefore of
+        /// all events emitted at or after the `i`th entry of `event_start_indexes`
+        senders: vector<address>,
+        /// The `i`th entry in this vector is the name of the `i`th inventory snapshot, taken at the
+        /// index of the global event log in the `i`th entry of `snapshot_indexes`
+        snapshot_names: vector<vector<u8>>,
+        snapshot_indexes: vector<u64>,
+    }
+
+    /// Describes the direct owner of an object in the inventory.
+    /// `kind` uses the same encoding as `ids_by_owner_kind`. `owner` is the owning address, or the
+    /// ID of the parent object, for the owned kinds and @0x0 for the immutable and shared kinds.
+    struct OwnerInfo has copy, drop {
+        kind: u8,
+        owner: address,
+    }
+
+    /// Describes an event of the global event log, as returned by `events_of_type_in_window`.
+    /// `recipient` holds the raw recipient bytes of the event, and `object_id` the ID of the object
+    /// the event is about, if any (user events are not about a particular object).
+    struct EventInfo has copy, drop {
+        recipient: vector<u8>,
+        object_id: Option<ID>,
+    }
+
+    /// The IDs of the live objects directly owned by the address with bytes `owner`, as returned by
+    /// `inventory_by_owner`.
+    struct OwnerObjects has copy, drop {
+        owner: vector<u8>,
+        ids: vector<ID>,
+    }
+
+    /// The differences between the inventories at two indices of the global event log, as returned
+    /// by `inventory_diff`. Each field holds the bytes of object IDs in increasing order.
+    struct InventoryDiff has copy, drop {
+        /// Objects only live at the later index
+        created: vector<vector<u8>>,
+        /// Objects only live at the earlier index, e.g. because they were deleted or wrapped
+        removed: vector<vector<u8>>,
+        /// Objects live at both indices, but with different owners
+        moved: vector<vector<u8>>,
+    }
+
+    /// A structural problem of the event at index `event_index` of the global event log, as
+    /// returned by `validate_event_log`.
+    struct EventLogProblem has copy, drop {
+        event_index: u64,
+        description: vector<u8>,
     }
 
     /// A wrapper for test_scenario to return an immutable object from the inventory
@@ -80,10 +141,14 @@ module sui::test_scenario {
 
     /// Begin a new multi-transaction test scenario in a context where `sender` is the tx sender
     public fun begin(sender: &address): Scenario {
+        mark_event_log_internal();
         Scenario {
             ctx: tx_context::new_from_hint(*sender, 0, 0, 0),
             removed: vector::empty(),
             event_start_indexes: vector[0],
+            senders: vector[*sender],
+            snapshot_names: vector::empty(),
+            snapshot_indexes: vector::empty(),
         }
     }
 
@@ -106,6 +171,7 @@ module sui::test_scenario {
         let tx_event_count = new_total_events - old_total_events;
         let event_end_index = last_tx_start_index + tx_event_count;
         vector::push_back(&mut scenario.event_start_indexes, event_end_index);
+        vector::push_back(&mut scenario.senders, *sender);
 
         // create a seed for new transaction digest to ensure that this tx has a different
         // digest (and consequently, different object ID's) than the previous tx
@@ -114,6 +180,53 @@ module sui::test_scenario {
         scenario.ctx = tx_context::new_from_hint(*sender, new_tx_digest_seed, epoch, 0);
     }
 
+    /// End the current transaction and start a new one with the same sender in which all objects
+    /// created so far are gone, as if the scenario had just begun.
+    /// Useful for running several independent phases in a single test function.
+    /// Note that indices into the global event log, such as the ones returned by `last_tx_index`,
+    /// still refer to the whole log, including the events before the reset.
+    public fun reset(scenario: &mut Scenario) {
+        let sender = sender(scenario);
+        next_tx(scenario, &sender);
+        scenario_reset();
+        // the new transaction starts after the reset marker
+        let last_tx = vector::length(&scenario.event_start_indexes) - 1;
+        *vector::borrow_mut(&mut scenario.event_start_indexes, last_tx) = num_events();
+    }
+
+    /// Record the current state of the inventory, including the objects transferred so far in the
+    /// current transaction, under `name`, replacing any earlier snapshot with the same name.
+    /// `restore_inventory` brings the inventory back to this state, e.g. to run several branches
+    /// of a test from a common starting point.
+    public fun snapshot_inventory(scenario: &mut Scenario, name: vector<u8>) {
+        let index = num_events();
+        snapshot_inventory_internal();
+        let (found, i) = vector::index_of(&scenario.snapshot_names, &name);
+        if (found) {
+            *vector::borrow_mut(&mut scenario.snapshot_indexes, i) = index;
+        } else {
+            vector::push_back(&mut scenario.snapshot_names, name);
+            vector::push_back(&mut scenario.snapshot_indexes, index);
+        }
+    }
+
+    /// End the current transaction and start a new one with the same sender in which the inventory
+    /// is in the state recorded by `snapshot_inventory` under `name`. Objects created since the
+    /// snapshot are gone and objects deleted since the snapshot are back. The snapshot remains
+    /// available, and so do the snapshots taken after it, even across a `reset`.
+    /// Aborts with `ESnapshotNotFound` if no snapshot was taken under `name`.
+    public fun restore_inventory(scenario: &mut Scenario, name: vector<u8>) {
+        let (found, i) = vector::index_of(&scenario.snapshot_names, &name);
+        assert!(found, ESnapshotNotFound);
+        let index = *vector::borrow(&scenario.snapshot_indexes, i);
+        let sender = sender(scenario);
+        next_tx(scenario, &sender);
+        restore_inventory_internal(index);
+        // the new transaction starts after the restore marker
+        let last_tx = vector::length(&scenario.event_start_indexes) - 1;
+        *vector::borrow_mut(&mut scenario.event_start_indexes, last_tx) = num_events();
+    }
+
     /// Advance the scenario to a new epoch.
     public fun next_epoch(scenario: &mut Scenario) {
         tx_context::increment_epoch_number(&mut scenario.ctx);
@@ -137,6 +250,44 @@ module sui::test_scenario {
         remove_unique_object_from_inventory(scenario, objects)
     }
 
+    /// Take the object of type `T` owned by the sender of the current transaction, as tracked by
+    /// `scenario`. Same as `take_owned`, under the name used by other Sui test helpers.
+    /// Aborts with `EEmptyInventory` if the sender owns no object of type `T`, and with
+    /// `EInventoryAmbiguity` if it owns several.
+    public fun take_from_sender<T: key>(scenario: &mut Scenario): T {
+        take_owned<T>(scenario)
+    }
+
+    /// Similar to take_owned, but also considers objects that the tx sender owns indirectly,
+    /// i.e. objects owned by an object that the sender owns (directly or indirectly).
+    /// Aborts if there is no such object of type `T`, or if there is more than one.
+    public fun take_owned_transitive<T: key>(scenario: &mut Scenario): T {
+        let signer_address = sender(scenario);
+        let objects: vector<T> = get_inventory_transitive<T>(
+            signer_address,
+            last_tx_start_index(scenario)
+        );
+        remove_unique_object_from_inventory(scenario, objects)
+    }
+
+    /// Take the only object of type `T` in the inventory of the tx sender.
+    /// Unlike `take_owned`, the failures are reported by dedicated abort codes:
+    /// `ENO_MATCHING_OBJECT` (112) if there is no such object, `EMULTIPLE_MATCHING_OBJECTS` (113)
+    /// if there are several.
+    public fun take_one<T: key>(scenario: &mut Scenario): T {
+        let object = take_one_owned_object<T>(sender(scenario), last_tx_start_index(scenario));
+        remove_unique_object_from_inventory(scenario, vector::singleton(object))
+    }
+
+    /// Take the object of type `T` at index `i` among the objects of type `T` that the tx sender owns
+    /// directly, in creation order: `i = 0` is the one transferred to anyone first. Unlike `take_owned`,
+    /// this picks a specific object when several identical objects were created, without their IDs.
+    /// Aborts with `EOBJECT_INDEX_OUT_OF_RANGE` (116) if the sender owns `i` such objects or fewer.
+    public fun take_owned_at_index<T: key>(scenario: &mut Scenario, i: u64): T {
+        let object = get_object_at_index<T>(i, sender(scenario), last_tx_start_index(scenario));
+        remove_unique_object_from_inventory(scenario, vector::singleton(object))
+    }
+
     /// Similar to take_owned, but only return objects that are immutable with type `T`.
     /// In this case, the sender is irrelevant.
     /// Returns a wrapper that only supports a `borrow` API to get the read-only reference.
@@ -151,6 +302,18 @@ module sui::test_scenario {
         }
     }
 
+    /// Same as `take_immutable`, but returns the immutable object of type `T` with object ID `id`.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no object with ID `id`, with
+    /// `ETYPE_MISMATCH` (103) if it is not of type `T`, and with `ENOT_IMMUTABLE` (108) if it is
+    /// not immutable, e.g. because it was shared instead.
+    public fun take_immutable_by_id<T: key>(scenario: &mut Scenario, id: ID): ImmutableWrapper<T> {
+        let object = take_immutable_object_by_id<T>(id::id_address(&id), last_tx_start_index(scenario));
+        let object = remove_unique_object_from_inventory(scenario, vector::singleton(object));
+        ImmutableWrapper {
+            object,
+        }
+    }
+
     /// Returns the underlying reference of an immutable object wrapper returned above.
     public fun borrow<T: key>(wrapper: &ImmutableWrapper<T>): &T {
         &wrapper.object
@@ -170,6 +333,32 @@ module sui::test_scenario {
         }
     }
 
+    /// Same as `take_shared`, but returns the shared object of type `T` with object ID `id`.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no object with ID `id`, with
+    /// `ETYPE_MISMATCH` (103) if it is not of type `T`, with `ENOT_SHARED` (106) if it is owned by
+    /// an address or another object, and with `ENotSharedObject` if it was frozen instead.
+    /// Aborts with `ESHARED_OBJECT_LOCKED` (115) if the object was locked by the current
+    /// transaction with `mark_shared_object_locked`.
+    public fun take_shared_by_id<T: key>(scenario: &mut Scenario, id: ID): SharedWrapper<T> {
+        assert_shared_object_unlocked(id::id_address(&id), last_tx_start_index(scenario));
+        let (object, owner_kind) = take_shared_object_by_id<T>(
+            id::id_address(&id),
+            last_tx_start_index(scenario),
+        );
+        assert!(owner_kind == SHARED_OWNER_KIND, ENotSharedObject);
+        let object = remove_unique_object_from_inventory(scenario, vector::singleton(object));
+        SharedWrapper {
+            object,
+        }
+    }
+
+    /// Record that the shared object with ID `id` is in use by the current transaction, as if another
+    /// transaction ordered concurrently by consensus held it. Taking the object with
+    /// `take_shared_by_id` then aborts until the next transaction (or `reset`) releases the lock.
+    public fun mark_shared_object_locked(id: ID) {
+        lock_shared_object_internal(id::id_address(&id))
+    }
+
     /// Returns the underlying mutable reference of a shared object.
     public fun borrow_mut<T: key>(wrapper: &mut SharedWrapper<T>): &mut T {
         &mut wrapper.object
@@ -213,6 +402,14 @@ module sui::test_scenario {
         remove_unique_object_from_inventory(scenario, objects)
     }
 
+    /// Same as `take_owned_by_id`, but the object does not need to be owned by the tx sender.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no object with ID `id`, and with
+    /// `ETYPE_MISMATCH` (103) if the object is not of type `T`.
+    public fun take_by_id<T: key>(scenario: &mut Scenario, id: ID): T {
+        let object = take_object_by_id<T>(id::id_address(&id), last_tx_start_index(scenario));
+        remove_unique_object_from_inventory(scenario, vector::singleton(object))
+    }
+
     /// This function tells you whether calling `take_owned_by_id` would succeed.
     /// It provides a way to check without triggering assertions.
     public fun can_take_owned_by_id<T: key>(scenario: &Scenario, id: ID): bool {
@@ -286,6 +483,14 @@ module sui::test_scenario {
         return_owned(scenario, object)
     }
 
+    /// Return an object that was extracted from a wrapper during the current transaction to the
+    /// global object pool, owned by `owner`.
+    /// This is an alternative to transferring the unwrapped object when the test only needs the
+    /// object to become available again.
+    public fun return_unwrapped<T: key>(obj: T, owner: address) {
+        emit_unwrapped_object_event(obj, owner)
+    }
+
     /// Return `true` if a call to `take_owned<T>(scenario)` will succeed
     public fun can_take_owned<T: key>(scenario: &Scenario): bool {
         let objects: vector<T> = get_account_owned_inventory<T>(
@@ -303,6 +508,441 @@ module sui::test_scenario {
         res
     }
 
+    /// Return `true` if the object with ID `id` is live in the global object pool of `scenario`,
+    /// regardless of its type or owner.
+    /// Objects transferred during the current transaction are not visible until `next_tx` is called.
+    public fun object_exists(scenario: &Scenario, id: ID): bool {
+        object_exists_for_testing(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the number of objects of type `T` in the inventory of `owner`.
+    /// Objects removed from the inventory during the current transaction are still counted.
+    public fun num_owned<T: key>(scenario: &Scenario, owner: address): u64 {
+        count_inventory<T>(owner, last_tx_start_index(scenario))
+    }
+
+    /// Return the number of live objects of any type owned by the address `owner`.
+    /// Objects owned indirectly, through another object, are not counted.
+    public fun num_objects_owned(scenario: &Scenario, owner: address): u64 {
+        objects_owned_count(owner, last_tx_start_index(scenario))
+    }
+
+    /// Abort with `EINVENTORY_NOT_EMPTY` (120) if the address `owner` directly owns any live object,
+    /// e.g. to check at the end of a test that an account was fully drained. Objects owned
+    /// indirectly, through another object, are not considered.
+    public fun assert_inventory_empty(scenario: &Scenario, owner: address) {
+        assert_inventory_empty_internal(owner, last_tx_start_index(scenario))
+    }
+
+    /// Return the bytes of every address that directly owns at least one live object, without
+    /// duplicates and in increasing order. Shared, immutable and object-owned objects are ignored.
+    public fun distinct_owners(scenario: &Scenario): vector<vector<u8>> {
+        get_distinct_owners(last_tx_start_index(scenario))
+    }
+
+    /// Return the bytes of the IDs of all live objects owned by the object with ID `parent`,
+    /// in increasing order. Only direct children are returned, not their own children.
+    public fun children_of(scenario: &Scenario, parent: ID): vector<vector<u8>> {
+        get_children(id::id_address(&parent), last_tx_start_index(scenario))
+    }
+
+    /// Return the ID of the object owning the object with ID `id`, the inverse of `children_of`.
+    /// Returns `option::none()` if the object is not owned by another object, or if there is no
+    /// live object with ID `id`.
+    public fun parent_object_of(scenario: &Scenario, id: ID): Option<ID> {
+        let ids = get_parent_object(id::id_address(&id), last_tx_start_index(scenario));
+        if (vector::is_empty(&ids)) {
+            option::none()
+        } else {
+            option::some(vector::pop_back(&mut ids))
+        }
+    }
+
+    /// Make the object with ID `id` unavailable from the next transaction on, e.g. to simulate an
+    /// object consumed by a hot potato, without recording a deletion: the object is not returned
+    /// by functions inspecting deletions such as `typed_deleted_object_ids` or `assert_deleted`.
+    /// Dropping an object that is not in the inventory has no effect.
+    public fun drop_from_inventory(id: ID) {
+        drop_object_internal(id::id_address(&id))
+    }
+
+    /// Delete all objects owned by the address `owner`, e.g. to simulate an account being drained.
+    /// Only objects directly owned by `owner` at the start of the current transaction are deleted;
+    /// their children are left as they are. The objects are gone from the next transaction on, and
+    /// the removals are recorded as deletions: the objects are returned by functions inspecting
+    /// deletions such as `typed_deleted_object_ids` or `assert_deleted`.
+    public fun clear_owner_inventory(scenario: &Scenario, owner: address) {
+        clear_owner_inventory_internal(owner, last_tx_start_index(scenario))
+    }
+
+    /// Return, for each address directly owning at least one live object, the IDs of the objects it
+    /// owns, sorted by address. Shared, immutable and object-owned objects are not included.
+    public fun inventory_by_owner(scenario: &Scenario): vector<OwnerObjects> {
+        get_inventory_by_owner(last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all live objects of type `T1` or `T2` in the global object pool of
+    /// `scenario`, whatever their owner, in increasing order, along with which type each object
+    /// has: the `i`th entry of the first vector is 0 if the `i`th object is a `T1` and 1 if it is
+    /// a `T2`. Useful for tests of heterogeneous collections.
+    /// A Move vector cannot hold objects of different types, so objects are identified by their ID,
+    /// and can be taken with `take_by_id`. Likewise, Move functions have a fixed number of type
+    /// parameters: use `ids_of_any_type_3` for three types.
+    public fun ids_of_any_type<T1: key, T2: key>(scenario: &Scenario): (vector<u64>, vector<ID>) {
+        objects_of_any_type_2<T1, T2>(last_tx_start_index(scenario))
+    }
+
+    /// Same as `ids_of_any_type`, for three types: the type index of a `T3` is 2.
+    public fun ids_of_any_type_3<T1: key, T2: key, T3: key>(
+        scenario: &Scenario,
+    ): (vector<u64>, vector<ID>) {
+        objects_of_any_type_3<T1, T2, T3>(last_tx_start_index(scenario))
+    }
+
+    /// Return the kind of owner of the live object with ID `id`, using the encoding of
+    /// `ids_by_owner_kind`: 0 = owned by an address, 1 = owned by another object,
+    /// 2 = immutable, 3 = shared. Returns 255 if there is no live object with this ID, e.g. because
+    /// it was deleted or wrapped.
+    public fun owner_kind_of(scenario: &Scenario, id: ID): u8 {
+        get_owner_kind(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return whether the live object with ID `id` is shared. Returns false if there is no live
+    /// object with this ID.
+    public fun is_shared_object(scenario: &Scenario, id: ID): bool {
+        is_shared_object_internal(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the number of live objects of any type in the global object pool of `scenario`,
+    /// whatever their owner: objects owned by an address or by another object, immutable and shared
+    /// objects. Deleted and wrapped objects are not counted, unlike in `total_distinct_objects`,
+    /// which counts every object transferred within a window of the event log. `num_objects_owned`
+    /// and `num_shared_objects` count a subset of these objects.
+    public fun num_live_objects(scenario: &Scenario): u64 {
+        count_live_objects(last_tx_start_index(scenario))
+    }
+
+    /// Return the number of live shared objects in the global object pool of `scenario`.
+    public fun num_shared_objects(scenario: &Scenario): u64 {
+        count_shared_objects(last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of the objects deleted while no longer live since strict delete checking was
+    /// enabled with `set_strict_delete_checking`, in deletion order. A `reset` clears the anomalies.
+    public fun delete_anomalies(scenario: &Scenario): vector<ID> {
+        get_delete_anomalies(last_tx_start_index(scenario))
+    }
+
+    /// Return the bytes of the address owning the live object with ID `id`, or of the ID of its
+    /// parent object if it is owned by another object. Returns an empty vector if the object is
+    /// shared or immutable, and if there is no live object with this ID.
+    /// Use `owner_kind_of` to tell an address owner from a parent object.
+    public fun owner_address_of(scenario: &Scenario, id: ID): vector<u8> {
+        get_owner_address(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all live objects of type `T` directly owned by any of the addresses whose
+    /// bytes are in `owners`, e.g. as returned by `distinct_owners`, in increasing order.
+    /// This replays the event log once, however many addresses are given.
+    /// Aborts with `EINVALID_ADDRESS_BYTES` (118) if an element of `owners` is not an address.
+    public fun ids_owned_by_any<T: key>(scenario: &Scenario, owners: vector<vector<u8>>): vector<ID> {
+        get_inventory_for_any<T>(owners, last_tx_start_index(scenario))
+    }
+
+    /// Return the sum of a `u64` field of all live objects of type `T` owned by the address `owner`,
+    /// e.g. the total balance of the coins of an account. `field_path` gives the index of the field
+    /// in the object (0 is its `VersionedID`), then in the struct stored in this field, and so on.
+    /// Aborts with `EINVALID_FIELD_PATH` (119) if the path does not lead to a `u64` field, and with
+    /// an arithmetic error if the sum overflows.
+    public fun sum_u64_field<T: key>(scenario: &Scenario, owner: address, field_path: vector<u64>): u64 {
+        sum_u64_field_internal<T>(owner, field_path, last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of the live objects of type `T` owned by the address `owner`, from the highest
+    /// to the lowest value of their `u64` field at `field_path` (see `sum_u64_field`), e.g. to find
+    /// the coin with the highest balance. Objects with the same value are in increasing order of ID.
+    /// Aborts with `EINVALID_FIELD_PATH` (119) if the path does not lead to a `u64` field.
+    public fun ids_sorted_by_u64_field<T: key>(
+        scenario: &Scenario,
+        owner: address,
+        field_path: vector<u64>,
+    ): vector<ID> {
+        get_inventory_sorted_by_u64<T>(owner, field_path, last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all live objects of type `T` whose owner is of kind `owner_kind`:
+    /// 0 = owned by the address `owner`, 1 = owned by the object with ID `owner`,
+    /// 2 = immutable, 3 = shared. `owner` is ignored for the immutable and shared kinds.
+    /// Unlike `take_owned`, this never mixes objects of different owner kinds, which makes it
+    /// possible to check that an object became shared rather than staying address-owned.
+    public fun ids_by_owner_kind<T: key>(scenario: &Scenario, owner: address, owner_kind: u8): vector<ID> {
+        get_inventory_by_owner_kind<T>(owner, last_tx_start_index(scenario), owner_kind)
+    }
+
+    /// Return true if the address `owner` owns at least one object of type `T`.
+    /// Cheaper and clearer than checking that the inventory returned by another function is non-empty.
+    public fun has_received_object_of_type<T: key>(scenario: &Scenario, owner: address): bool {
+        has_object_of_type<T>(owner, last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all live objects of type `T` owned by the address `owner`.
+    /// If `include_shared` is true, the IDs of all shared objects of type `T` are included as well;
+    /// otherwise only objects owned by `owner` itself are returned.
+    public fun owned_ids<T: key>(scenario: &Scenario, owner: address, include_shared: bool): vector<ID> {
+        get_inventory_filtered<T>(owner, include_shared, last_tx_start_index(scenario))
+    }
+
+    /// Same as calling `owned_ids` without shared objects for each of `T1` and `T2`, but the
+    /// global event log is only processed once.
+    public fun owned_ids_2<T1: key, T2: key>(scenario: &Scenario, owner: address): (vector<ID>, vector<ID>) {
+        let (objects1, objects2) = get_inventories_2<T1, T2>(owner, last_tx_start_index(scenario));
+        (ids_of(objects1), ids_of(objects2))
+    }
+
+    /// Same as calling `owned_ids` without shared objects for each of `T1`, `T2` and `T3`, but the
+    /// global event log is only processed once.
+    public fun owned_ids_3<T1: key, T2: key, T3: key>(
+        scenario: &Scenario,
+        owner: address,
+    ): (vector<ID>, vector<ID>, vector<ID>) {
+        let (objects1, objects2, objects3) = get_inventories_3<T1, T2, T3>(
+            owner,
+            last_tx_start_index(scenario),
+        );
+        (ids_of(objects1), ids_of(objects2), ids_of(objects3))
+    }
+
+    /// Return the ID of the object of type `T` most recently transferred to `owner`, provided
+    /// `owner` still owns it. Returns `option::none()` if there is no such object.
+    public fun most_recent_id_for_owner<T: key>(scenario: &Scenario, owner: address): Option<ID> {
+        let ids = most_recent_object_id_for_owner<T>(owner, last_tx_start_index(scenario));
+        if (vector::is_empty(&ids)) {
+            option::none()
+        } else {
+            option::some(vector::pop_back(&mut ids))
+        }
+    }
+
+    /// Same as `take_by_id`, but also checks that the object has the version `version`, e.g. to
+    /// make sure a test operates on the expected state of an object that several transactions
+    /// mutate. Aborts with `EOBJECT_NOT_FOUND` (102) if there is no object with ID `id`, with
+    /// `ETYPE_MISMATCH` (103) if the object is not of type `T`, and with `EVERSION_MISMATCH` (124)
+    /// if it has another version.
+    public fun take_by_id_at_version<T: key>(scenario: &mut Scenario, id: ID, version: u64): T {
+        let object = take_object_by_id_at_version<T>(
+            id::id_address(&id),
+            version,
+            last_tx_start_index(scenario),
+        );
+        remove_unique_object_from_inventory(scenario, vector::singleton(object))
+    }
+
+    /// Return the version of the object with ID `id` in the global object pool of `scenario`.
+    /// Aborts if there is no such object.
+    public fun object_version(scenario: &Scenario, id: ID): u64 {
+        object_version_for_testing(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the size in bytes of the serialized contents of the object with ID `id` in the
+    /// global object pool of `scenario`. Aborts if there is no such object.
+    public fun object_size(scenario: &Scenario, id: ID): u64 {
+        object_size_for_testing(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the BCS serialization of the contents of the object with ID `id` in the global object
+    /// pool of `scenario`, e.g. to compare it against golden bytes or decode specific fields.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object.
+    public fun object_bytes(scenario: &Scenario, id: ID): vector<u8> {
+        object_bytes_for_testing(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Return the BCS serialization of the `MoveTypeLayout` of the object with ID `id` in the global
+    /// object pool of `scenario`, which external tools need to decode the bytes returned by
+    /// `object_bytes`. Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object.
+    public fun object_layout(scenario: &Scenario, id: ID): vector<u8> {
+        object_layout_bcs(id::id_address(&id), last_tx_start_index(scenario))
+    }
+
+    /// Replace the contents of the object of type `T` with ID `id` by the value serialized in
+    /// `object_bytes`, as returned by `object_bytes` after editing some fields, e.g. to set up an
+    /// object in a given state without running the logic that would mutate it. The object keeps
+    /// its owner, and has its new contents from the next transaction on.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object, with `ETYPE_MISMATCH` (103)
+    /// if it is not a `T`, and with `EINVALID_OBJECT_BYTES` (121) unless `object_bytes` is the
+    /// serialization of a `T` with ID `id`.
+    public fun replace_object<T: key>(scenario: &Scenario, id: ID, object_bytes: vector<u8>) {
+        replace_object_for_testing<T>(
+            id::id_address(&id),
+            object_bytes,
+            last_tx_start_index(scenario),
+        )
+    }
+
+    /// Abort with `ETYPE_MISMATCH` (103) unless the objects with IDs `id1` and `id2` in the global
+    /// object pool of `scenario` have the same type, e.g. to check that a polymorphic function
+    /// produced an object of the type of its input without naming that type.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if either object does not exist.
+    public fun assert_same_type(scenario: &Scenario, id1: ID, id2: ID) {
+        assert_same_type_internal(
+            id::id_address(&id1),
+            id::id_address(&id2),
+            last_tx_start_index(scenario),
+        )
+    }
+
+    /// Return whether the objects with IDs `id1` and `id2` in the global object pool of `scenario`
+    /// have byte-for-byte identical BCS serializations, e.g. to check that a mutation did not change
+    /// an object. Since the serialization starts with the `VersionedID` of the object, two distinct
+    /// objects are never equal: use `objects_equal_ignoring_id` to compare a copy with its source.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if either object does not exist.
+    public fun objects_equal(scenario: &Scenario, id1: ID, id2: ID): bool {
+        objects_equal_for_testing(
+            id::id_address(&id1),
+            id::id_address(&id2),
+            last_tx_start_index(scenario),
+        )
+    }
+
+    /// Return whether the objects with IDs `id1` and `id2` in the global object pool of `scenario`
+    /// have the same type and contents, e.g. to check that a copy is identical to its source.
+    /// The `VersionedID` of the objects is not compared, since it differs for any two objects.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if either object does not exist.
+    public fun objects_equal_ignoring_id(scenario: &Scenario, id1: ID, id2: ID): bool {
+        objects_equal_ignoring_id_for_testing(
+            id::id_address(&id1),
+            id::id_address(&id2),
+            last_tx_start_index(scenario),
+        )
+    }
+
+    /// Return a human-readable, JSON-like description of the object with ID `id` in the global
+    /// object pool of `scenario`, including its type, owner, version and contents.
+    /// Intended as a debugging aid. Aborts if there is no such object.
+    public fun debug_object_json(scenario: &Scenario, id: ID): String {
+        utf8::string_unsafe(debug_object_json_bytes(id::id_address(&id), last_tx_start_index(scenario)))
+    }
+
+    /// Return the IDs of all objects that are currently wrapped in another object, i.e. that were
+    /// wrapped and have not been unwrapped, transferred or deleted since. Wrapped and deleted objects
+    /// are both absent from the global object pool, so this tells the two apart.
+    public fun wrapped_ids(scenario: &Scenario): vector<ID> {
+        wrapped_object_ids(last_tx_start_index(scenario))
+    }
+
+    /// Return a 32-byte fingerprint of the global object pool of `scenario`, covering the ID, owner,
+    /// type and contents of every object. Useful to check that an operation changed nothing, or
+    /// that it did change something.
+    public fun inventory_digest(scenario: &Scenario): vector<u8> {
+        get_inventory_digest(last_tx_start_index(scenario))
+    }
+
+    /// Return the fully qualified name of the type of the object with ID `id` in the global object
+    /// pool of `scenario`, including type arguments, e.g. `0x2::coin::Coin<0x2::sui::SUI>`.
+    /// Useful to understand why a `take_*` function did not find an object. Aborts if there is no
+    /// such object.
+    public fun type_of_object(scenario: &Scenario, id: ID): String {
+        utf8::string_unsafe(object_type_name(id::id_address(&id), last_tx_start_index(scenario)))
+    }
+
+    /// Abort with `ETYPE_TAG_MISMATCH` (117) unless the fully qualified name of the type of the
+    /// object with ID `id`, as returned by `type_of_object`, is `expected`, e.g.
+    /// `b"0x2::coin::Coin<0x2::sui::SUI>"`. Useful when the type of the object is not available as a
+    /// type parameter at the call site. Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object.
+    public fun assert_type_tag(scenario: &Scenario, id: ID, expected: vector<u8>) {
+        assert_object_type_tag(id::id_address(&id), expected, last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all objects in the global object pool of `scenario` that have been frozen,
+    /// regardless of their type.
+    public fun frozen_ids(scenario: &Scenario): vector<ID> {
+        frozen_object_ids(last_tx_start_index(scenario))
+    }
+
+    /// Return the IDs of all objects of type `T` that `signer_address` can access, either because it
+    /// owns them or because they are children of its objects, along with the direct owner of each.
+    /// The `i`th `OwnerInfo` describes the owner of the `i`th ID.
+    public fun ids_with_owners<T: key>(
+        scenario: &Scenario,
+        signer_address: address,
+    ): (vector<ID>, vector<OwnerInfo>) {
+        let (objects, owners) = get_inventory_with_owners<T>(
+            signer_address,
+            last_tx_start_index(scenario),
+        );
+        (ids_of(objects), owners)
+    }
+
+    /// Return the IDs of all live objects of type `T` in the global object pool of `scenario`,
+    /// whatever their owner, along with the direct owner of each. The `i`th `OwnerInfo` describes
+    /// the owner of the `i`th ID. Useful for global assertions, e.g. on the total number of coins.
+    public fun all_ids_of_type<T: key>(scenario: &Scenario): (vector<ID>, vector<OwnerInfo>) {
+        all_objects_of_type<T>(last_tx_start_index(scenario))
+    }
+
+    /// Return the owner kind described by `info`, see `ids_by_owner_kind` for the encoding
+    public fun owner_info_kind(info: &OwnerInfo): u8 {
+        info.kind
+    }
+
+    /// Return the owning address or parent object ID described by `info`
+    public fun owner_info_address(info: &OwnerInfo): address {
+        info.owner
+    }
+
+    /// Return the bytes of the owning address described by `objects`
+    public fun owner_objects_owner(objects: &OwnerObjects): vector<u8> {
+        objects.owner
+    }
+
+    /// Return the IDs of the objects owned by the address described by `objects`, in increasing order
+    public fun owner_objects_ids(objects: &OwnerObjects): vector<ID> {
+        objects.ids
+    }
+
+    /// Return the bytes of the IDs of the objects created between the two inventories of `diff`
+    public fun inventory_diff_created(diff: &InventoryDiff): vector<vector<u8>> {
+        diff.created
+    }
+
+    /// Return the bytes of the IDs of the objects removed between the two inventories of `diff`
+    public fun inventory_diff_removed(diff: &InventoryDiff): vector<vector<u8>> {
+        diff.removed
+    }
+
+    /// Return the bytes of the IDs of the objects that changed owner between the two inventories
+    /// of `diff`
+    public fun inventory_diff_moved(diff: &InventoryDiff): vector<vector<u8>> {
+        diff.moved
+    }
+
+    /// Return the index in the global event log of the event with the problem described by `problem`
+    public fun event_log_problem_index(problem: &EventLogProblem): u64 {
+        problem.event_index
+    }
+
+    /// Return a human-readable description of the problem described by `problem`
+    public fun event_log_problem_description(problem: &EventLogProblem): String {
+        utf8::string_unsafe(problem.description)
+    }
+
+    /// Return the raw recipient bytes of the event described by `info`
+    public fun event_info_recipient(info: &EventInfo): vector<u8> {
+        info.recipient
+    }
+
+    /// Return the ID of the object the event described by `info` is about, if any
+    public fun event_info_object_id(info: &EventInfo): Option<ID> {
+        info.object_id
+    }
+
+    /// Abort unless the object with ID `id` is owned by the address `owner`.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object, with
+    /// `ENOT_ADDRESS_OWNED` (104) if it is shared, immutable or owned by another object,
+    /// and with `EWRONG_OWNER` (105) if it is owned by a different address.
+    public fun assert_owned_by(scenario: &Scenario, id: ID, owner: address) {
+        assert_object_owned_by(id::id_address(&id), owner, last_tx_start_index(scenario))
+    }
+
     /// Return the `TxContext` associated with this `scenario`
     public fun ctx(scenario: &mut Scenario): &mut TxContext {
         &mut scenario.ctx
@@ -332,6 +972,19 @@ module sui::test_scenario {
         *vector::borrow(idxs, tx_idx)
     }
 
+    /// Return the sender of the transaction that emitted the event at index `event_idx` of the
+    /// global event log. Aborts if no event has been emitted at this index yet.
+    public fun event_sender(scenario: &Scenario, event_idx: u64): address {
+        assert!(event_idx < num_events(), EInvalidEventIndex);
+        let idxs = &scenario.event_start_indexes;
+        let tx_idx = vector::length(idxs) - 1;
+        // the first start index is always 0, so this terminates
+        while (*vector::borrow(idxs, tx_idx) > event_idx) {
+            tx_idx = tx_idx - 1;
+        };
+        *vector::borrow(&scenario.senders, tx_idx)
+    }
+
     /// Return the tx start index of the current transaction. This is an index into the global event log
     /// such that all events emitted by the current transaction occur at or after this index
     fun last_tx_start_index(scenario: &Scenario): u64 {
@@ -376,6 +1029,19 @@ module sui::test_scenario {
         object_opt
     }
 
+    /// Return the IDs of `objects` in their original order, dropping the objects themselves
+    fun ids_of<T: key>(objects: vector<T>): vector<ID> {
+        let ids = vector::empty();
+        while (!vector::is_empty(&objects)) {
+            let object = vector::pop_back(&mut objects);
+            vector::push_back(&mut ids, *id::id(&object));
+            drop_object_for_testing(object);
+        };
+        vector::destroy_empty(objects);
+        vector::reverse(&mut ids);
+        ids
+    }
+
     // TODO: Add API's for inspecting user events, printing the user's inventory, ...
 
     // ---Natives---
@@ -393,11 +1059,179 @@ module sui::test_scenario {
         tx_end_index: u64,
     ): vector<T>;
 
+    /// Return the number of live objects of type `T` that can be accessed by `signer_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun count_inventory<T: key>(signer_address: address, tx_end_index: u64): u64;
+
+    /// Return all live objects of type `T` owned by `owner_address`, either directly or through a chain
+    /// of objects owned by `owner_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_transitive<T: key>(owner_address: address, tx_end_index: u64): vector<T>;
+
+    /// Return the IDs of all live objects of type `T` whose owner is exactly the one described by
+    /// `owner_kind` (see `ids_by_owner_kind`) and `owner_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_by_owner_kind<T: key>(
+        owner_address: address,
+        tx_end_index: u64,
+        owner_kind: u8,
+    ): vector<ID>;
+
     /// Return all live objects of type `T` that's not owned, i.e. either immutable or shared.
     /// `immutable` indicates whether we want to return immutable object or shared.
     /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
     native fun get_unowned_inventory<T: key>(immutable: bool, tx_end_index: u64): vector<T>;
 
+    /// Return true if an object with ID `object_id` is live in the inventory, regardless of its type or owner.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_exists_for_testing(object_id: address, tx_end_index: u64): bool;
+
+    /// Print every live object in the global object pool to stderr, along with its type and owner.
+    /// Intended purely as a debugging aid, e.g. when an inventory assertion fails unexpectedly.
+    public native fun print_inventory();
+
+    /// Print the log of transfers, freezes, deletes and wraps emitted at or after the index
+    /// `tx_begin_idx` of the global event log to stderr. Intended purely as a debugging aid.
+    public native fun print_transfer_log(tx_begin_idx: u64);
+
+    /// Return the position in the global event log that the next event will be written to.
+    /// Capture this at the start of a logical transaction and pass it as the `tx_begin_idx`
+    /// of functions that inspect the events emitted since then, e.g. `print_transfer_log`.
+    public native fun last_tx_index(): u64;
+
+    /// Check that every event of the global event log has the shape the inventory expects, and
+    /// return the problems found, in log order: events of unknown types, events the ID of their
+    /// object cannot be read from, and transfers to invalid addresses. Any such problem makes the
+    /// functions inspecting the inventory fail, so an empty result is a good pre-flight check when
+    /// emitting events by hand.
+    public native fun validate_event_log(): vector<EventLogProblem>;
+
+    /// Return the number of events of the given type emitted by all txes in the current VM
+    /// execution. `event_type` is either one of the `sui::event::EventType` values
+    /// (e.g. 0 for transfers to an address, 6 for user events) or 255 for object wrapping.
+    public native fun num_events_of_type(event_type: u8): u64;
+
+    /// Return the number of events emitted since the most recent `reset`, or by all txes in the
+    /// current VM execution if the scenario was never reset. Indices into the global event log
+    /// keep counting from the start of the log, including the events before the reset.
+    public native fun num_events_since_reset(): u64;
+
+    /// Return the number of distinct objects transferred, frozen or shared by the events emitted
+    /// in the window `[tx_begin_idx, tx_end_idx)` of the global event log, including objects that
+    /// were deleted or wrapped since. Unlike `num_events`, an object transferred several times
+    /// is only counted once.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun total_distinct_objects(tx_begin_idx: u64, tx_end_idx: u64): u64;
+
+    /// Return an `EventInfo` for each event of the given type emitted in the window
+    /// `[tx_begin_idx, tx_end_idx)` of the global event log, in emission order.
+    /// `event_type` uses the same encoding as `num_events_of_type`.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun events_of_type_in_window(
+        event_type: u8,
+        tx_begin_idx: u64,
+        tx_end_idx: u64,
+    ): vector<EventInfo>;
+
+    /// Return the bytes of the recipient of the last transfer to an address emitted at or after
+    /// the index `tx_begin_idx` of the global event log, or an empty vector if there is none.
+    public native fun most_recent_receiver(tx_begin_idx: u64): vector<u8>;
+
+    /// Return the IDs of all objects that were live after the first `n` events of the global event
+    /// log, e.g. an index captured with `last_tx_index`. Useful for bisecting which transaction
+    /// introduced or removed an object.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if fewer than `n` events were emitted.
+    public native fun inventory_ids_at(n: u64): vector<ID>;
+
+    /// Return the bytes of the IDs of all objects that were given at least two different owners
+    /// by the events emitted in the window `[tx_begin_idx, tx_end_idx)` of the global event log,
+    /// e.g. an object transferred to two different addresses within one transaction.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun detect_id_conflicts(tx_begin_idx: u64, tx_end_idx: u64): vector<vector<u8>>;
+
+    /// Abort with `EOBJECTS_NOT_CONSERVED` (114) unless the number of live objects at the index
+    /// `tx_end_idx` of the global event log equals the number at `tx_begin_idx`, plus the objects
+    /// created and minus the objects deleted or wrapped by the events in between.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun assert_no_objects_leaked(tx_begin_idx: u64, tx_end_idx: u64);
+
+    /// Abort with `EOBJECT_NOT_DELETED` (110) unless the object with ID `id` was deleted at or after
+    /// the index `tx_begin_idx` of the global event log, e.g. an index captured with `last_tx_index`.
+    /// Wrapping an object does not count as deleting it.
+    public fun assert_deleted(id: ID, tx_begin_idx: u64) {
+        assert_deleted_internal(id::id_address(&id), tx_begin_idx)
+    }
+
+    /// Abort with `EOBJECT_NOT_WRAPPED` (111) unless the object with ID `id` was wrapped in another
+    /// object at or after the index `tx_begin_idx` of the global event log. Wrapping is only
+    /// recorded when the transaction ends, so call this after `next_tx`.
+    public fun assert_wrapped(id: ID, tx_begin_idx: u64) {
+        assert_wrapped_internal(id::id_address(&id), tx_begin_idx)
+    }
+
+    /// Return the IDs of the objects transferred to `recipient` at or after the index
+    /// `tx_begin_idx` of the global event log that `recipient` still owns, in transfer order.
+    /// Objects that were later deleted, wrapped or transferred to someone else are excluded.
+    public native fun transferred_object_ids_to(recipient: address, tx_begin_idx: u64): vector<ID>;
+
+    /// Enable or disable strict delete checking for the events emitted from now on. In strict mode,
+    /// deleting the ID of an object that was live at some point but no longer is, e.g. because it was
+    /// already deleted or dropped, is recorded as an anomaly reported by `delete_anomalies`.
+    /// Deleting an ID that was never used by a transferred object is always allowed.
+    public native fun set_strict_delete_checking(enabled: bool);
+
+    /// Compare the inventory built from the first `before` events of the global event log with the
+    /// one built from the first `after` events, e.g. indices captured with `last_tx_index`.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) unless `before <= after` and at least
+    /// `after` events were emitted.
+    public native fun inventory_diff(before: u64, after: u64): InventoryDiff;
+
+    /// Return the index in the global event log of the first event transferring, freezing or sharing
+    /// the object with ID `id`, i.e. the event that created it, to be compared with indices captured
+    /// with `last_tx_index`. Returns the maximum `u64` value if the object never appeared in the log.
+    public fun object_created_at(id: ID): u64 {
+        object_created_at_internal(id::id_address(&id))
+    }
+
+    /// Return the index in the global event log of the last event about the object with ID `id`,
+    /// e.g. a transfer, freeze, mutation, wrap or deletion, to check that the object was not touched
+    /// since an index captured with `last_tx_index`. Returns the maximum `u64` value if the object
+    /// never appeared in the log.
+    public fun object_last_touched_at(id: ID): u64 {
+        object_last_touched_at_internal(id::id_address(&id))
+    }
+
+    /// Return how many times the object with ID `id` was transferred to an address or to an object
+    /// by the events emitted in the window `[tx_begin_idx, tx_end_idx)` of the global event log,
+    /// e.g. to check that an object moved exactly once. Freezing or sharing it does not count.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public fun transfer_count_for(id: ID, tx_begin_idx: u64, tx_end_idx: u64): u64 {
+        transfer_count_internal(id::id_address(&id), tx_begin_idx, tx_end_idx)
+    }
+
+    /// Return whether the address `owner` directly owned the object with ID `id` at some point of
+    /// the window `[tx_begin_idx, tx_end_idx)` of the global event log: at the start of the window,
+    /// or after any event of the window, e.g. to check that an object passed through an account
+    /// that no longer holds it.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public fun ever_owned(owner: address, id: ID, tx_begin_idx: u64, tx_end_idx: u64): bool {
+        ever_owned_internal(owner, id::id_address(&id), tx_begin_idx, tx_end_idx)
+    }
+
+    /// Return the IDs of the objects created at or after the index `tx_begin_idx` of the global
+    /// event log, in creation order, i.e. the objects transferred, frozen or shared for the first
+    /// time. Unlike `transferred_object_ids_to`, objects that existed before are never included,
+    /// even when they were transferred again in the window.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if `tx_begin_idx` is beyond the end of the log.
+    public native fun created_object_ids(tx_begin_idx: u64): vector<ID>;
+
+    /// Return the IDs of the objects of type `T` deleted at or after the index `tx_begin_idx`
+    /// of the global event log, in deletion order.
+    /// Objects that were created and deleted within the same transaction are only reported if they
+    /// were deleted with `delete_object_for_testing`, since other deletions do not record the type.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if `tx_begin_idx` is beyond the end of the log.
+    public native fun typed_deleted_object_ids<T: key>(tx_begin_idx: u64): vector<ID>;
+
     /// Test-only function for dropping an arbitrary object.
     /// Useful for eliminating objects without the `drop` ability.
     /// Note that this doesn't delete the object from anywhere.
@@ -405,6 +1239,38 @@ module sui::test_scenario {
     /// that could not be done in normal code path.
     native fun drop_object_for_testing<T>(t: T);
 
+    /// Test-only function for deleting an arbitrary object.
+    /// Unlike `drop_object_for_testing`, this emits a deletion event for the object's ID,
+    /// so the object no longer shows up in the inventory of subsequent transactions.
+    /// Useful for disposing of objects whose type does not expose a destructor.
+    public native fun delete_object_for_testing<T: key>(obj: T);
+
+    /// Test-only function for freezing an arbitrary object, as if it had been frozen with
+    /// `transfer::freeze_object`. The object is immutable from the next transaction on.
+    /// Useful for setting up immutable objects whose module does not expose a way to freeze them.
+    public native fun freeze_object_for_testing<T: key>(obj: T);
+
+    /// Test-only function making `obj` a child of the object with ID `parent`, as if it had been
+    /// transferred with `transfer::transfer_to_object`. The parent does not need to be available
+    /// to the caller, which makes it quick to set up object hierarchies.
+    /// Aborts with `EINVALID_PARENT` (107) if `parent` is the ID of `obj` itself.
+    public fun transfer_to_object_for_testing<T: key>(obj: T, parent: ID) {
+        transfer_to_object_internal(obj, id::id_address(&parent))
+    }
+
+    /// Emit the event making `obj` a child of the object with ID `parent`.
+    native fun transfer_to_object_internal<T: key>(obj: T, parent: address);
+
+    /// Test-only function changing the owner of the live object with ID `id` without going
+    /// through the transfer functions, e.g. to set up states that are awkward to reach otherwise.
+    /// `owner_kind` uses the encoding of `ids_by_owner_kind`; `owner` is the new owning address, or
+    /// the ID of the new parent object, and is ignored for the immutable and shared kinds.
+    /// The change is visible from the next transaction on, like a regular transfer.
+    /// Aborts with `EINVALID_PARENT` (107) if `owner` is the ID of the object itself.
+    public fun change_owner_for_testing(id: ID, owner_kind: u8, owner: address) {
+        change_owner_internal(id::id_address(&id), owner_kind, owner)
+    }
+
     /// Return the total number of events emitted by all txes in the current VM execution, including both user-defined events and system events
     native fun num_events(): u64;
 
@@ -413,4 +1279,363 @@ module sui::test_scenario {
 
     /// Update the content of an object in the inventory.
     native fun update_object<T: key>(obj: T);
+
+    /// Return the ID of the object of type `T` most recently transferred to `owner_address` that is
+    /// still owned by `owner_address`, as a vector with zero or one element.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun most_recent_object_id_for_owner<T: key>(owner_address: address, tx_end_index: u64): vector<ID>;
+
+    /// Return the version of the live object with ID `object_id`.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_version_for_testing(object_id: address, tx_end_index: u64): u64;
+
+    /// Return the IDs of all live immutable objects.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun frozen_object_ids(tx_end_index: u64): vector<ID>;
+
+    /// Return the live object with ID `object_id`, regardless of its owner.
+    /// Aborts with `EOBJECT_NOT_FOUND` (102) if there is no such object, and with
+    /// `ETYPE_MISMATCH` (103) if it is not of type `T`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun take_object_by_id<T: key>(object_id: address, tx_end_index: u64): T;
+
+    /// Emit an event recording that `obj` was extracted from a wrapper, so that it re-enters the
+    /// inventory owned by `owner`.
+    native fun emit_unwrapped_object_event<T: key>(obj: T, owner: address);
+
+    /// Return all live objects of type `T` that `signer_address` can access, either directly or as
+    /// children of its objects, along with the direct owner of each object.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_with_owners<T: key>(
+        signer_address: address,
+        tx_end_index: u64,
+    ): (vector<T>, vector<OwnerInfo>);
+
+    /// Return the IDs of all live objects of type `T`, whatever their owner, along with the direct
+    /// owner of each.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun all_objects_of_type<T: key>(tx_end_index: u64): (vector<ID>, vector<OwnerInfo>);
+
+    /// Abort unless the live object with ID `object_id` is owned by `owner_address`.
+    /// See `assert_owned_by` for the abort codes.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun assert_object_owned_by(object_id: address, owner_address: address, tx_end_index: u64);
+
+    /// Emit a marker telling the inventory to forget all events emitted before it.
+    native fun scenario_reset();
+
+    /// Return the shared or immutable object of type `T` with ID `object_id`, regardless of the tx
+    /// sender, along with its owner kind. Aborts if the object is owned.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun take_shared_object_by_id<T: key>(object_id: address, tx_end_index: u64): (T, u8);
+
+    /// Return the IDs of all live objects of type `T` owned by `owner_address`, and additionally of
+    /// all shared objects of type `T` if `include_shared` is true.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_filtered<T: key>(
+        owner_address: address,
+        include_shared: bool,
+        tx_end_index: u64,
+    ): vector<ID>;
+
+    /// Return true if `owner_address` owns at least one live object of type `T`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun has_object_of_type<T: key>(owner_address: address, tx_end_index: u64): bool;
+
+    /// Return the size in bytes of the serialized contents of the live object with ID `object_id`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_size_for_testing(object_id: address, tx_end_index: u64): u64;
+
+    /// Return the serialized contents of the live object with ID `object_id`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_bytes_for_testing(object_id: address, tx_end_index: u64): vector<u8>;
+
+    /// Return whether the live objects with IDs `object_id1` and `object_id2` have the same BCS
+    /// serialization.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun objects_equal_for_testing(object_id1: address, object_id2: address, tx_end_index: u64): bool;
+
+    /// Return whether the live objects with IDs `object_id1` and `object_id2` have the same type
+    /// and contents, leaving their `VersionedID` out.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun objects_equal_ignoring_id_for_testing(
+        object_id1: address,
+        object_id2: address,
+        tx_end_index: u64,
+    ): bool;
+
+    /// Return the UTF-8 encoded description of the live object with ID `object_id`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun debug_object_json_bytes(object_id: address, tx_end_index: u64): vector<u8>;
+
+    /// Return the IDs of all objects that are currently wrapped.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun wrapped_object_ids(tx_end_index: u64): vector<ID>;
+
+    /// Return the SHA3-256 digest of all live objects, in increasing order of ID.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_digest(tx_end_index: u64): vector<u8>;
+
+    /// Return the immutable object of type `T` with ID `object_id`. Aborts if it is not immutable.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun take_immutable_object_by_id<T: key>(object_id: address, tx_end_index: u64): T;
+
+    /// Return the number of live objects of any type owned by `owner_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun objects_owned_count(owner_address: address, tx_end_index: u64): u64;
+
+    /// Return the bytes of every address that directly owns a live object, in increasing order.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_distinct_owners(tx_end_index: u64): vector<vector<u8>>;
+
+    /// Emit the event moving the live object with ID `object_id` to the given owner.
+    native fun change_owner_internal(object_id: address, owner_kind: u8, owner: address);
+
+    /// Abort unless the object with ID `object_id` was deleted at or after the index `tx_begin_idx`.
+    native fun assert_deleted_internal(object_id: address, tx_begin_idx: u64);
+
+    /// Abort unless the object with ID `object_id` was wrapped at or after the index `tx_begin_idx`.
+    native fun assert_wrapped_internal(object_id: address, tx_begin_idx: u64);
+
+    /// Return the UTF-8 encoded name of the type of the live object with ID `object_id`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_type_name(object_id: address, tx_end_index: u64): vector<u8>;
+
+    /// Return the live objects of types `T1` and `T2` owned by `owner_address`, one vector per type.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventories_2<T1: key, T2: key>(
+        owner_address: address,
+        tx_end_index: u64,
+    ): (vector<T1>, vector<T2>);
+
+    /// Same as `get_inventories_2`, for three types.
+    native fun get_inventories_3<T1: key, T2: key, T3: key>(
+        owner_address: address,
+        tx_end_index: u64,
+    ): (vector<T1>, vector<T2>, vector<T3>);
+
+    /// Return the bytes of the IDs of all live objects owned by the object with ID `parent_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_children(parent_address: address, tx_end_index: u64): vector<vector<u8>>;
+
+    /// Return the only live object of type `T` that can be accessed by `signer_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun take_one_owned_object<T: key>(signer_address: address, tx_end_index: u64): T;
+
+    /// Emit the marker removing the object with ID `object_id` from the inventory.
+    native fun drop_object_internal(object_id: address);
+
+    /// Return the IDs of the live objects directly owned by each address, sorted by address.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_by_owner(tx_end_index: u64): vector<OwnerObjects>;
+
+    /// Return the owner kind of the live object with ID `object_id`, or 255 if there is none.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_owner_kind(object_id: address, tx_end_index: u64): u8;
+
+    /// Emit the marker locking the shared object with ID `object_id` for the current transaction.
+    native fun lock_shared_object_internal(object_id: address);
+
+    /// Abort if the shared object with ID `object_id` was locked at or after the index `tx_begin_idx`.
+    native fun assert_shared_object_unlocked(object_id: address, tx_begin_idx: u64);
+
+    /// Return the IDs of the objects deleted while no longer live in strict delete checking mode.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_delete_anomalies(tx_end_index: u64): vector<ID>;
+
+    /// Return the live object of type `T` owned by `owner_address` at index `i` in creation order.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_object_at_index<T: key>(i: u64, owner_address: address, tx_end_index: u64): T;
+
+    /// Abort unless the type of the live object with ID `object_id` is rendered as `expected`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun assert_object_type_tag(object_id: address, expected: vector<u8>, tx_end_index: u64);
+
+    /// Return the bytes of the owner of the live object with ID `object_id`, if it is owned.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_owner_address(object_id: address, tx_end_index: u64): vector<u8>;
+
+    /// Return the index of the first event transferring, freezing or sharing `object_id`.
+    native fun object_created_at_internal(object_id: address): u64;
+
+    /// Return the index of the last event about `object_id`.
+    native fun object_last_touched_at_internal(object_id: address): u64;
+
+    /// Return the IDs of all live objects of type `T` owned by any of the addresses in `owner_addresses`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_for_any<T: key>(
+        owner_addresses: vector<vector<u8>>,
+        tx_end_index: u64,
+    ): vector<ID>;
+
+    /// Return the number of transfers of `object_id` in the window `[tx_begin_idx, tx_end_idx)`.
+    native fun transfer_count_internal(object_id: address, tx_begin_idx: u64, tx_end_idx: u64): u64;
+
+    /// Return the sum of the `u64` fields at `field_path` of the live objects of type `T` owned by
+    /// `owner_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun sum_u64_field_internal<T: key>(
+        owner_address: address,
+        field_path: vector<u64>,
+        tx_end_index: u64,
+    ): u64;
+
+    /// Delete all objects owned by `owner_address`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun clear_owner_inventory_internal(owner_address: address, tx_end_index: u64);
+
+    /// Emit the marker identifying the global event log, which lets the natives reuse the work
+    /// done by earlier queries of the same log.
+    native fun mark_event_log_internal();
+
+    /// Emit the marker recording the current state of the inventory as a snapshot.
+    native fun snapshot_inventory_internal();
+
+    /// Emit the marker restoring the state of the inventory recorded by the snapshot marker at
+    /// index `snapshot_index` of the global event log.
+    native fun restore_inventory_internal(snapshot_index: u64);
+
+    /// Return whether the live object with ID `object_id` is shared.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun is_shared_object_internal(object_id: address, tx_end_index: u64): bool;
+
+    /// Return the serialized layout of the live object with ID `object_id`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun object_layout_bcs(object_id: address, tx_end_index: u64): vector<u8>;
+
+    /// Abort unless `owner_address` directly owns no live object.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun assert_inventory_empty_internal(owner_address: address, tx_end_index: u64);
+
+    /// Return the bytes of the IDs of the objects that the address `from` directly owns in the
+    /// inventory built from the first `before` events of the global event log, and that the address
+    /// `to` directly owns in the one built from the first `after` events, in increasing order.
+    /// This checks e.g. that an escrow released exactly the expected objects to their recipient.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) unless `before <= after` and at least
+    /// `after` events were emitted.
+    public native fun ids_moved_between(
+        from: address,
+        to: address,
+        before: u64,
+        after: u64,
+    ): vector<vector<u8>>;
+
+    /// Return the number of live shared objects.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun count_shared_objects(tx_end_index: u64): u64;
+
+    /// Return the IDs of all live objects of type `T1` or `T2`, along with the index of the type
+    /// argument matching each.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun objects_of_any_type_2<T1: key, T2: key>(tx_end_index: u64): (vector<u64>, vector<ID>);
+
+    /// Same as `objects_of_any_type_2`, for three types.
+    native fun objects_of_any_type_3<T1: key, T2: key, T3: key>(
+        tx_end_index: u64,
+    ): (vector<u64>, vector<ID>);
+
+    /// Replace the contents of the live object of type `T` with ID `object_id` by the value
+    /// serialized in `object_bytes`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun replace_object_for_testing<T: key>(
+        object_id: address,
+        object_bytes: vector<u8>,
+        tx_end_index: u64,
+    );
+
+    /// Return the bytes of the IDs of all the objects that the events emitted in the window
+    /// `[tx_begin_idx, tx_end_idx)` of the global event log are about, in increasing order, e.g. to
+    /// audit everything a transaction touched. Unlike `created_object_ids`, this includes objects
+    /// that were only updated, deleted or wrapped, and IDs deleted without ever being used by an
+    /// object. User events are not about any object.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun all_seen_object_ids(tx_begin_idx: u64, tx_end_idx: u64): vector<vector<u8>>;
+
+    /// Return the number of events of each category emitted in the window
+    /// `[tx_begin_idx, tx_end_idx)` of the global event log, as a vector of 6 counts in this order:
+    /// transfers to an address, transfers to an object, freezes, deletions (of an ID or of a child
+    /// object), wraps and user events. Shares, updates of objects returned to the inventory and
+    /// other `test_scenario` markers are not counted.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun event_histogram(tx_begin_idx: u64, tx_end_idx: u64): vector<u64>;
+
+    /// Return whether `owner_address` directly owned `object_id` at some point of the window
+    /// `[tx_begin_idx, tx_end_idx)`.
+    native fun ever_owned_internal(
+        owner_address: address,
+        object_id: address,
+        tx_begin_idx: u64,
+        tx_end_idx: u64,
+    ): bool;
+
+    /// Abort unless the events emitted in the window `[tx_begin_idx, tx_end_idx)` of the global
+    /// event log leave the ownership of all objects as it was, e.g. for transactions that should
+    /// be read-only. Objects may be mutated in place.
+    /// Aborts with `ELIVE_OBJECTS_CHANGED` (122) if the live objects at the end of the window are not
+    /// the ones at its start, i.e. objects were created, deleted or wrapped, and with
+    /// `EOBJECT_OWNER_CHANGED` (123) if an object has a different owner at the end of the window.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun assert_no_ownership_changes(tx_begin_idx: u64, tx_end_idx: u64);
+
+    /// Return the IDs of the live objects of type `T` owned by `owner_address`, in decreasing order
+    /// of their `u64` field at `field_path` and then in increasing order of ID.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_inventory_sorted_by_u64<T: key>(
+        owner_address: address,
+        field_path: vector<u64>,
+        tx_end_index: u64,
+    ): vector<ID>;
+
+    /// Return the ID of the parent of the live object with ID `object_id`, as a vector with zero or
+    /// one element.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun get_parent_object(object_id: address, tx_end_index: u64): vector<ID>;
+
+    /// Emit a user event carrying `tag` and `payload`, e.g. to record checkpoints of a test in the
+    /// event log. Unlike `event::emit`, the payload does not need to be a struct: tests serialize
+    /// whatever they need to record. Like any user event, it does not affect the inventory.
+    public native fun emit_user_event_for_testing(tag: u64, payload: vector<u8>);
+
+    /// Return the payloads of the user events emitted with `emit_user_event_for_testing` and the tag
+    /// `tag` in the window `[tx_begin_idx, tx_end_idx)` of the global event log, in the order they
+    /// were emitted. Returns an empty vector if there are none.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun user_events_with_tag(
+        tag: u64,
+        tx_begin_idx: u64,
+        tx_end_idx: u64,
+    ): vector<vector<u8>>;
+
+    /// Abort unless the live objects with IDs `object_id1` and `object_id2` have the same type.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun assert_same_type_internal(object_id1: address, object_id2: address, tx_end_index: u64);
+
+    /// Return the number of live objects.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun count_live_objects(tx_end_index: u64): u64;
+
+    /// Return the bytes of the IDs of the objects wrapped by the events emitted in the window
+    /// `[tx_begin_idx, tx_end_idx)` of the global event log that are still inside their wrapper at
+    /// the end of the window, i.e. that were not unwrapped, transferred or deleted since, in
+    /// increasing order. Useful to check that a workflow unwrapped everything it wrapped. Wrapping
+    /// is only recorded when the transaction ends, so call this after `next_tx`.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun dangling_wrapped_ids(tx_begin_idx: u64, tx_end_idx: u64): vector<vector<u8>>;
+
+    /// Return the live object of type `T` with ID `object_id`, provided it has the version
+    /// `expected_version`.
+    /// Events at or beyond `tx_end_index` in the log should not be processed to build this inventory
+    native fun take_object_by_id_at_version<T: key>(
+        object_id: address,
+        expected_version: u64,
+        tx_end_index: u64,
+    ): T;
+
+    /// Return how the number of live objects directly owned by the address `owner` changed over
+    /// the window `[tx_begin_idx, tx_end_idx)` of the global event log, e.g. to check that an
+    /// account gained 3 objects in a transaction. Since Move has no signed integers, the change is
+    /// returned as its magnitude and whether it is a decrease: `(3, false)` for a gain of 3 objects.
+    /// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` (109) if the window is not within the log.
+    public native fun owned_count_delta(owner: address, tx_begin_idx: u64, tx_end_idx: u64): (u64, bool);
 }
@@ -8,9 +8,12 @@ mod transfer;
 mod tx_context;
 
 use move_binary_format::errors::PartialVMError;
-use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, vm_status::StatusCode,
+};
 use move_vm_runtime::native_functions::{NativeFunction, NativeFunctionTable};
 use move_vm_types::values::{Struct, Value};
+use sui_types::base_types::SequenceNumber;
 
 pub fn all_natives(
     move_stdlib_addr: AccountAddress,
@@ -21,37 +24,498 @@ pub fn all_natives(
         ("id", "bytes_to_address", id::bytes_to_address),
         ("id", "delete_id", id::delete_id),
         ("id", "get_versioned_id", id::get_versioned_id),
+        (
+            "test_scenario",
+            "all_objects_of_type",
+            test_scenario::all_objects_of_type,
+        ),
+        (
+            "test_scenario",
+            "all_seen_object_ids",
+            test_scenario::all_seen_object_ids,
+        ),
+        (
+            "test_scenario",
+            "assert_deleted_internal",
+            test_scenario::assert_deleted_internal,
+        ),
+        (
+            "test_scenario",
+            "assert_inventory_empty_internal",
+            test_scenario::assert_inventory_empty_internal,
+        ),
+        (
+            "test_scenario",
+            "assert_no_objects_leaked",
+            test_scenario::assert_no_objects_leaked,
+        ),
+        (
+            "test_scenario",
+            "assert_no_ownership_changes",
+            test_scenario::assert_no_ownership_changes,
+        ),
+        (
+            "test_scenario",
+            "assert_object_owned_by",
+            test_scenario::assert_object_owned_by,
+        ),
+        (
+            "test_scenario",
+            "assert_object_type_tag",
+            test_scenario::assert_object_type_tag,
+        ),
+        (
+            "test_scenario",
+            "assert_same_type_internal",
+            test_scenario::assert_same_type_internal,
+        ),
+        (
+            "test_scenario",
+            "assert_shared_object_unlocked",
+            test_scenario::assert_shared_object_unlocked,
+        ),
+        (
+            "test_scenario",
+            "assert_wrapped_internal",
+            test_scenario::assert_wrapped_internal,
+        ),
+        (
+            "test_scenario",
+            "change_owner_internal",
+            test_scenario::change_owner_internal,
+        ),
+        (
+            "test_scenario",
+            "clear_owner_inventory_internal",
+            test_scenario::clear_owner_inventory_internal,
+        ),
+        (
+            "test_scenario",
+            "count_inventory",
+            test_scenario::count_inventory,
+        ),
+        (
+            "test_scenario",
+            "count_live_objects",
+            test_scenario::count_live_objects,
+        ),
+        (
+            "test_scenario",
+            "count_shared_objects",
+            test_scenario::count_shared_objects,
+        ),
+        (
+            "test_scenario",
+            "created_object_ids",
+            test_scenario::created_object_ids,
+        ),
+        (
+            "test_scenario",
+            "dangling_wrapped_ids",
+            test_scenario::dangling_wrapped_ids,
+        ),
+        (
+            "test_scenario",
+            "debug_object_json_bytes",
+            test_scenario::debug_object_json_bytes,
+        ),
+        (
+            "test_scenario",
+            "delete_object_for_testing",
+            test_scenario::delete_object_for_testing,
+        ),
+        (
+            "test_scenario",
+            "detect_id_conflicts",
+            test_scenario::detect_id_conflicts,
+        ),
         (
             "test_scenario",
             "drop_object_for_testing",
             test_scenario::drop_object_for_testing,
         ),
+        (
+            "test_scenario",
+            "drop_object_internal",
+            test_scenario::drop_object_internal,
+        ),
+        (
+            "test_scenario",
+            "emit_unwrapped_object_event",
+            test_scenario::emit_unwrapped_object_event,
+        ),
+        (
+            "test_scenario",
+            "emit_user_event_for_testing",
+            test_scenario::emit_user_event_for_testing,
+        ),
         (
             "test_scenario",
             "emit_wrapped_object_events",
             test_scenario::emit_wrapped_object_events,
         ),
+        (
+            "test_scenario",
+            "event_histogram",
+            test_scenario::event_histogram,
+        ),
+        (
+            "test_scenario",
+            "events_of_type_in_window",
+            test_scenario::events_of_type_in_window,
+        ),
+        (
+            "test_scenario",
+            "ever_owned_internal",
+            test_scenario::ever_owned_internal,
+        ),
+        (
+            "test_scenario",
+            "freeze_object_for_testing",
+            test_scenario::freeze_object_for_testing,
+        ),
+        (
+            "test_scenario",
+            "frozen_object_ids",
+            test_scenario::frozen_object_ids,
+        ),
         (
             "test_scenario",
             "get_account_owned_inventory",
             test_scenario::get_account_owned_inventory,
         ),
+        ("test_scenario", "get_children", test_scenario::get_children),
+        (
+            "test_scenario",
+            "get_delete_anomalies",
+            test_scenario::get_delete_anomalies,
+        ),
+        (
+            "test_scenario",
+            "get_distinct_owners",
+            test_scenario::get_distinct_owners,
+        ),
+        (
+            "test_scenario",
+            "get_inventories_2",
+            test_scenario::get_inventories,
+        ),
+        (
+            "test_scenario",
+            "get_inventories_3",
+            test_scenario::get_inventories,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_by_owner",
+            test_scenario::get_inventory_by_owner,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_by_owner_kind",
+            test_scenario::get_inventory_by_owner_kind,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_digest",
+            test_scenario::get_inventory_digest,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_filtered",
+            test_scenario::get_inventory_filtered,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_for_any",
+            test_scenario::get_inventory_for_any,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_sorted_by_u64",
+            test_scenario::get_inventory_sorted_by_u64,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_transitive",
+            test_scenario::get_inventory_transitive,
+        ),
+        (
+            "test_scenario",
+            "get_inventory_with_owners",
+            test_scenario::get_inventory_with_owners,
+        ),
+        (
+            "test_scenario",
+            "get_object_at_index",
+            test_scenario::get_object_at_index,
+        ),
         (
             "test_scenario",
             "get_object_owned_inventory",
             test_scenario::get_object_owned_inventory,
         ),
+        (
+            "test_scenario",
+            "get_owner_address",
+            test_scenario::get_owner_address,
+        ),
+        (
+            "test_scenario",
+            "get_owner_kind",
+            test_scenario::get_owner_kind,
+        ),
+        (
+            "test_scenario",
+            "get_parent_object",
+            test_scenario::get_parent_object,
+        ),
         (
             "test_scenario",
             "get_unowned_inventory",
             test_scenario::get_unowned_inventory,
         ),
+        (
+            "test_scenario",
+            "has_object_of_type",
+            test_scenario::has_object_of_type,
+        ),
+        (
+            "test_scenario",
+            "ids_moved_between",
+            test_scenario::ids_moved_between,
+        ),
+        (
+            "test_scenario",
+            "inventory_diff",
+            test_scenario::inventory_diff,
+        ),
+        (
+            "test_scenario",
+            "inventory_ids_at",
+            test_scenario::inventory_ids_at,
+        ),
+        (
+            "test_scenario",
+            "is_shared_object_internal",
+            test_scenario::is_shared_object_internal,
+        ),
+        (
+            "test_scenario",
+            "last_tx_index",
+            test_scenario::last_tx_index,
+        ),
+        (
+            "test_scenario",
+            "lock_shared_object_internal",
+            test_scenario::lock_shared_object_internal,
+        ),
+        (
+            "test_scenario",
+            "mark_event_log_internal",
+            test_scenario::mark_event_log_internal,
+        ),
+        (
+            "test_scenario",
+            "most_recent_object_id_for_owner",
+            test_scenario::most_recent_object_id_for_owner,
+        ),
+        (
+            "test_scenario",
+            "most_recent_receiver",
+            test_scenario::most_recent_receiver,
+        ),
         ("test_scenario", "num_events", test_scenario::num_events),
+        (
+            "test_scenario",
+            "num_events_of_type",
+            test_scenario::num_events_of_type,
+        ),
+        (
+            "test_scenario",
+            "num_events_since_reset",
+            test_scenario::num_events_since_reset,
+        ),
+        (
+            "test_scenario",
+            "object_bytes_for_testing",
+            test_scenario::object_bytes_for_testing,
+        ),
+        (
+            "test_scenario",
+            "object_created_at_internal",
+            test_scenario::object_created_at_internal,
+        ),
+        (
+            "test_scenario",
+            "object_exists_for_testing",
+            test_scenario::object_exists_for_testing,
+        ),
+        (
+            "test_scenario",
+            "object_last_touched_at_internal",
+            test_scenario::object_last_touched_at_internal,
+        ),
+        (
+            "test_scenario",
+            "object_layout_bcs",
+            test_scenario::object_layout_bcs,
+        ),
+        (
+            "test_scenario",
+            "object_size_for_testing",
+            test_scenario::object_size_for_testing,
+        ),
+        (
+            "test_scenario",
+            "object_type_name",
+            test_scenario::object_type_name,
+        ),
+        (
+            "test_scenario",
+            "object_version_for_testing",
+            test_scenario::object_version_for_testing,
+        ),
+        (
+            "test_scenario",
+            "objects_equal_for_testing",
+            test_scenario::objects_equal_for_testing,
+        ),
+        (
+            "test_scenario",
+            "objects_equal_ignoring_id_for_testing",
+            test_scenario::objects_equal_ignoring_id_for_testing,
+        ),
+        (
+            "test_scenario",
+            "objects_of_any_type_2",
+            test_scenario::objects_of_any_type,
+        ),
+        (
+            "test_scenario",
+            "objects_of_any_type_3",
+            test_scenario::objects_of_any_type,
+        ),
+        (
+            "test_scenario",
+            "objects_owned_count",
+            test_scenario::objects_owned_count,
+        ),
+        (
+            "test_scenario",
+            "owned_count_delta",
+            test_scenario::owned_count_delta,
+        ),
+        (
+            "test_scenario",
+            "print_inventory",
+            test_scenario::print_inventory,
+        ),
+        (
+            "test_scenario",
+            "print_transfer_log",
+            test_scenario::print_transfer_log,
+        ),
+        (
+            "test_scenario",
+            "replace_object_for_testing",
+            test_scenario::replace_object_for_testing,
+        ),
+        (
+            "test_scenario",
+            "restore_inventory_internal",
+            test_scenario::restore_inventory_internal,
+        ),
+        (
+            "test_scenario",
+            "scenario_reset",
+            test_scenario::scenario_reset,
+        ),
+        (
+            "test_scenario",
+            "set_strict_delete_checking",
+            test_scenario::set_strict_delete_checking,
+        ),
+        (
+            "test_scenario",
+            "snapshot_inventory_internal",
+            test_scenario::snapshot_inventory_internal,
+        ),
+        (
+            "test_scenario",
+            "sum_u64_field_internal",
+            test_scenario::sum_u64_field_internal,
+        ),
+        (
+            "test_scenario",
+            "take_immutable_object_by_id",
+            test_scenario::take_immutable_object_by_id,
+        ),
+        (
+            "test_scenario",
+            "take_object_by_id",
+            test_scenario::take_object_by_id,
+        ),
+        (
+            "test_scenario",
+            "take_object_by_id_at_version",
+            test_scenario::take_object_by_id_at_version,
+        ),
+        (
+            "test_scenario",
+            "take_one_owned_object",
+            test_scenario::take_one_owned_object,
+        ),
+        (
+            "test_scenario",
+            "take_shared_object_by_id",
+            test_scenario::take_shared_object_by_id,
+        ),
+        (
+            "test_scenario",
+            "total_distinct_objects",
+            test_scenario::total_distinct_objects,
+        ),
+        (
+            "test_scenario",
+            "transfer_count_internal",
+            test_scenario::transfer_count_internal,
+        ),
+        (
+            "test_scenario",
+            "transfer_to_object_internal",
+            test_scenario::transfer_to_object_internal,
+        ),
+        (
+            "test_scenario",
+            "transferred_object_ids_to",
+            test_scenario::transferred_object_ids_to,
+        ),
+        (
+            "test_scenario",
+            "typed_deleted_object_ids",
+            test_scenario::typed_deleted_object_ids,
+        ),
         (
             "test_scenario",
             "update_object",
             test_scenario::update_object,
         ),
+        (
+            "test_scenario",
+            "user_events_with_tag",
+            test_scenario::user_events_with_tag,
+        ),
+        (
+            "test_scenario",
+            "validate_event_log",
+            test_scenario::validate_event_log,
+        ),
+        (
+            "test_scenario",
+            "wrapped_object_ids",
+            test_scenario::wrapped_object_ids,
+        ),
         (
             "transfer",
             "delete_child_object_internal",
@@ -88,6 +552,13 @@ pub fn get_object_id(object: Value) -> Result<Value, PartialVMError> {
     get_nested_struct_field(object, &[0, 0, 0, 0])
 }
 
+// Object { id: VersionedID { id: UniqueID { .. }, version: u64 } .. }
+// Extract the version of the object from its VersionedID.
+pub fn get_object_version(object: Value) -> Result<SequenceNumber, PartialVMError> {
+    let version = get_nested_struct_field(object, &[0, 1])?.value_as::<u64>()?;
+    Ok(SequenceNumber::from_u64(version))
+}
+
 // Extract a field valye that's nested inside value `v`. The offset of each nesting
 // is determined by `offsets`.
 pub fn get_nested_struct_field(mut v: Value, offsets: &[usize]) -> Result<Value, PartialVMError> {
@@ -99,5 +570,8 @@ pub fn get_nested_struct_field(mut v: Value, offsets: &[usize]) -> Result<Value,
 
 pub fn get_nth_struct_field(v: Value, n: usize) -> Result<Value, PartialVMError> {
     let mut itr = v.value_as::<Struct>()?.unpack()?;
-    Ok(itr.nth(n).unwrap())
+    itr.nth(n).ok_or_else(|| {
+        PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR)
+            .with_message(format!("struct has no field at index {}", n))
+    })
 }
@@ -2,36 +2,177 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::EventType;
-use core::panic;
-use move_binary_format::errors::PartialVMResult;
-use move_core_types::{account_address::AccountAddress, value::MoveTypeLayout};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_schedule::{GasCarrier, InternalGasUnits},
+    value::{MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
+};
 use move_vm_runtime::native_functions::NativeContext;
 use move_vm_types::{
     gas_schedule::NativeCostIndex,
     loaded_data::runtime_types::Type,
     natives::function::{native_gas, NativeResult},
     pop_arg,
-    values::{StructRef, Value, VectorRef},
+    values::{Struct, StructRef, Value, VectorRef},
 };
 use num_enum::TryFromPrimitive;
+use sha3::{Digest, Sha3_256};
 use smallvec::smallvec;
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+};
 use sui_types::{
-    base_types::{ObjectID, SuiAddress},
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     object::Owner,
 };
 
-use super::{get_nested_struct_field, get_object_id};
+use super::{get_nested_struct_field, get_nth_struct_field, get_object_id, get_object_version};
 
 type Event = (Vec<u8>, u64, Type, MoveTypeLayout, Value);
 
+// Event type bytes of the test_scenario-only events. They share the event log with the events
+// described by `EventType`, so none of them may be the discriminant of an `EventType` variant.
+// Every sentinel must be listed in `SENTINEL_EVENTS`, which the tests check for collisions.
+
+/// An object removed from the inventory during a transaction without appearing in any event.
 const WRAPPED_OBJECT_EVENT: u64 = 255;
+/// An object mutated in place, e.g. returned with `return_owned`.
 const UPDATE_OBJECT_EVENT: u64 = 254;
+/// A previously wrapped object made available again to the address recorded in the event.
+const UNWRAPPED_OBJECT_EVENT: u64 = 253;
+/// Marker emitted by `scenario_reset`. Everything before the most recent marker is ignored
+/// when rebuilding the inventory, except for the snapshots taken before it.
+const RESET_EVENT: u64 = 252;
+/// Marker emitted by `drop_object_internal`. The object leaves the inventory as if it had been
+/// deleted, but is not reported by the natives inspecting deletions.
+const DROPPED_OBJECT_EVENT: u64 = 251;
+/// Marker emitted by `lock_shared_object_internal`, holding the ID of a shared object in use by
+/// the transaction that emitted it. It does not affect the inventory.
+const LOCKED_SHARED_OBJECT_EVENT: u64 = 250;
+/// Marker emitted by `set_strict_delete_checking`, holding whether deletions of objects that are
+/// no longer live are recorded as anomalies by the events after it.
+const STRICT_DELETE_CHECKING_EVENT: u64 = 249;
+/// Marker emitted by `snapshot_inventory_internal`. The state of the inventory at this marker can
+/// be restored later by a `RESTORED_SNAPSHOT_EVENT` holding the index of the marker in the log.
+const SNAPSHOT_EVENT: u64 = 248;
+/// Marker emitted by `restore_inventory_internal`, holding the index in the log of the
+/// `SNAPSHOT_EVENT` whose state replaces the current state of the inventory.
+const RESTORED_SNAPSHOT_EVENT: u64 = 247;
+/// Marker emitted by `mark_event_log_internal` when a scenario begins, holding an identifier of
+/// the event log that is unique within the process. It does not affect the inventory, but lets
+/// the replays of the log be resumed from checkpoints of the same log.
+const EVENT_LOG_ID_EVENT: u64 = 246;
+
+const SENTINEL_EVENTS: [u64; 10] = [
+    WRAPPED_OBJECT_EVENT,
+    UPDATE_OBJECT_EVENT,
+    UNWRAPPED_OBJECT_EVENT,
+    RESET_EVENT,
+    DROPPED_OBJECT_EVENT,
+    LOCKED_SHARED_OBJECT_EVENT,
+    STRICT_DELETE_CHECKING_EVENT,
+    SNAPSHOT_EVENT,
+    RESTORED_SNAPSHOT_EVENT,
+    EVENT_LOG_ID_EVENT,
+];
 
 /// When transfer an object to a parent object, the parent object
 /// is not found in the inventory.
 const EPARENT_OBJECT_NOT_FOUND: u64 = 100;
 
+/// The owner kind passed to a native is not one of the `*_OWNER_KIND` discriminants.
+const EINVALID_OWNER_KIND: u64 = 101;
+
+/// Object of given ID cannot be found in the inventory.
+const EOBJECT_NOT_FOUND: u64 = 102;
+
+/// Object of given ID was found in the inventory, but it is not of the requested type.
+const ETYPE_MISMATCH: u64 = 103;
+
+/// Object of given ID was found in the inventory, but it is not owned by an address.
+const ENOT_ADDRESS_OWNED: u64 = 104;
+
+/// Object of given ID was found in the inventory, but it is owned by another address.
+const EWRONG_OWNER: u64 = 105;
+
+/// Object of given ID was found in the inventory, but it is not shared.
+const ENOT_SHARED: u64 = 106;
+
+/// Attempted to make an object its own parent.
+const EINVALID_PARENT: u64 = 107;
+
+/// Object of given ID was found in the inventory, but it is not immutable.
+const ENOT_IMMUTABLE: u64 = 108;
+
+/// An index into the event log is beyond the number of events emitted so far.
+const EEVENT_INDEX_OUT_OF_BOUNDS: u64 = 109;
+
+/// No deletion of the object of given ID was found in the inspected events.
+const EOBJECT_NOT_DELETED: u64 = 110;
+
+/// No wrapping of the object of given ID was found in the inspected events.
+const EOBJECT_NOT_WRAPPED: u64 = 111;
+
+/// Expected exactly one object of the requested type, but found none.
+const ENO_MATCHING_OBJECT: u64 = 112;
+
+/// Expected exactly one object of the requested type, but found more than one.
+const EMULTIPLE_MATCHING_OBJECTS: u64 = 113;
+
+/// The number of live objects at the end of a window of the event log does not match the number
+/// at its start plus the objects created minus the objects deleted within it.
+const EOBJECTS_NOT_CONSERVED: u64 = 114;
+
+/// Shared object of given ID is already in use by the current transaction.
+const ESHARED_OBJECT_LOCKED: u64 = 115;
+
+/// Requested the object at an index beyond the number of matching objects.
+const EOBJECT_INDEX_OUT_OF_RANGE: u64 = 116;
+
+/// Object of given ID was found in the inventory, but its type tag is not the expected one.
+const ETYPE_TAG_MISMATCH: u64 = 117;
+
+/// Bytes passed as an address do not have the length of a `SuiAddress`.
+const EINVALID_ADDRESS_BYTES: u64 = 118;
+
+/// A field path does not lead to a `u64` field in an object of the requested type.
+const EINVALID_FIELD_PATH: u64 = 119;
+
+/// An address expected to own no object directly owns at least one live object.
+const EINVENTORY_NOT_EMPTY: u64 = 120;
+
+/// Bytes given as the new contents of an object are not the serialization of an object of its
+/// type with its ID.
+const EINVALID_OBJECT_BYTES: u64 = 121;
+
+/// An object was created, deleted or wrapped within a window of the event log expected to leave
+/// the set of live objects unchanged.
+const ELIVE_OBJECTS_CHANGED: u64 = 122;
+
+/// An object has a different owner at the end of a window of the event log than at its start.
+const EOBJECT_OWNER_CHANGED: u64 = 123;
+
+/// Object of given ID was found in the inventory, but it does not have the requested version.
+const EVERSION_MISMATCH: u64 = 124;
+
+/// Discriminants used by natives to describe which `Owner` variant they refer to.
+const ADDRESS_OWNER_KIND: u8 = 0;
+const OBJECT_OWNER_KIND: u8 = 1;
+const IMMUTABLE_OWNER_KIND: u8 = 2;
+const SHARED_OWNER_KIND: u8 = 3;
+/// Owner kind reported for objects that are not in the inventory.
+const NOT_FOUND_OWNER_KIND: u8 = 255;
+
+/// Event index reported for objects that do not appear in the event log.
+const NOT_FOUND_EVENT_INDEX: u64 = u64::MAX;
+
+/// Size of the BCS serialization of a `VersionedID`: the bytes of its ID followed by its version.
+const VERSIONED_ID_SIZE: usize = ObjectID::LENGTH + std::mem::size_of::<u64>();
+
 #[derive(Debug)]
 struct OwnedObj {
     value: Value,
@@ -45,45 +186,373 @@ struct OwnedObj {
     /// signer will always be set eventually, but it needs to be optional first
     /// since we may not know its signer initially.
     signer: Option<Owner>,
+    /// Version of the object as recorded in its `VersionedID` by the last event that carried
+    /// the object's value.
+    version: SequenceNumber,
+    /// Layout of the object's value, as recorded by the last event that carried the object's
+    /// value. Needed by natives that re-serialize inventory objects.
+    layout: MoveTypeLayout,
 }
 
 /// Set of all live objects in the current test scenario
-// TODO: add a native function that prints the inventory for debugging purposes
-// This will require extending NativeContext with a function to map `Type` (which is just an index
-// into the module's StructHandle table for structs) to something human-readable like `TypeTag`.
-// TODO: add a native function that prints the log of transfers, deletes, wraps for debugging purposes
 type Inventory = BTreeMap<ObjectID, OwnedObj>;
 
+/// Return the error reported when the event log does not have the shape test_scenario expects.
+fn malformed_event_log(message: String) -> PartialVMError {
+    PartialVMError::new(StatusCode::DATA_FORMAT_ERROR)
+        .with_message(format!("Malformed test_scenario event log: {}", message))
+}
+
+/// Parse the `EventType` of a system or user event.
+/// Sentinel bytes are never parsed, even if `EventType` grows a variant with the same discriminant.
+fn parse_event_type(event_type_byte: u64) -> PartialVMResult<EventType> {
+    if SENTINEL_EVENTS.contains(&event_type_byte) {
+        return Err(malformed_event_log(format!(
+            "test_scenario event {} is not an EventType",
+            event_type_byte
+        )));
+    }
+    u8::try_from(event_type_byte)
+        .ok()
+        .and_then(|byte| EventType::try_from_primitive(byte).ok())
+        .ok_or_else(|| malformed_event_log(format!("unknown event type {}", event_type_byte)))
+}
+
 /// Return the object ID involved in an event.
 /// This depends on the value format for each event type.
-fn get_object_id_from_event(event_type_byte: u64, val: &Value) -> Option<ObjectID> {
-    let val = val.copy_value().unwrap();
-    let address = if event_type_byte == WRAPPED_OBJECT_EVENT {
+fn get_object_id_from_event(
+    event_type_byte: u64,
+    val: &Value,
+) -> PartialVMResult<Option<ObjectID>> {
+    if event_type_byte == RESET_EVENT
+        || event_type_byte == LOCKED_SHARED_OBJECT_EVENT
+        || event_type_byte == STRICT_DELETE_CHECKING_EVENT
+        || event_type_byte == SNAPSHOT_EVENT
+        || event_type_byte == RESTORED_SNAPSHOT_EVENT
+        || event_type_byte == EVENT_LOG_ID_EVENT
+    {
+        return Ok(None);
+    }
+    let val = val.copy_value()?;
+    let address = if event_type_byte == WRAPPED_OBJECT_EVENT
+        || event_type_byte == DROPPED_OBJECT_EVENT
+    {
         val
-    } else if event_type_byte == UPDATE_OBJECT_EVENT {
-        get_object_id(val).unwrap()
+    } else if event_type_byte == UPDATE_OBJECT_EVENT || event_type_byte == UNWRAPPED_OBJECT_EVENT {
+        get_object_id(val)?
     } else {
-        let event_type = EventType::try_from_primitive(event_type_byte as u8).unwrap();
-        match event_type {
+        match parse_event_type(event_type_byte)? {
             EventType::DeleteChildObject => val,
-            EventType::DeleteObjectID => get_nested_struct_field(val, &[0, 0, 0]).unwrap(),
+            EventType::DeleteObjectID => return Ok(Some(get_deleted_id_bytes(&val)?.into())),
             EventType::User => {
-                return None;
+                return Ok(None);
             }
-            _ => get_object_id(val.copy_value().unwrap()).unwrap(),
+            _ => get_object_id(val)?,
+        }
+    };
+    Ok(Some(address.value_as::<AccountAddress>()?.into()))
+}
+
+/// Return the ID carried by the `VersionedID` of a `DeleteObjectID` event, i.e. the field
+/// `VersionedID.id.id.bytes`, or an error if `versioned_id` does not have this shape.
+fn get_deleted_id_bytes(versioned_id: &Value) -> PartialVMResult<AccountAddress> {
+    get_nested_struct_field(versioned_id.copy_value()?, &[0, 0, 0])
+        .and_then(|bytes| bytes.value_as::<AccountAddress>())
+        .map_err(|_| {
+            malformed_event_log("deleted ID does not have the shape of a VersionedID".to_string())
+        })
+}
+
+/// Return whether `event_type_byte` describes an event giving an object a new owner: a transfer to
+/// an address or an object, a freeze or a share.
+fn is_transfer_event(event_type_byte: u64) -> bool {
+    !SENTINEL_EVENTS.contains(&event_type_byte)
+        && matches!(
+            EventType::try_from_primitive(event_type_byte as u8),
+            Ok(EventType::TransferToAddress
+                | EventType::TransferToObject
+                | EventType::FreezeObject
+                | EventType::ShareObject)
+        )
+}
+
+/// Return the owner that an event of kind `event_type_byte` gives to its object, or None if the
+/// event does not give its object a new owner. `recipient` must be a `SuiAddress` for
+/// `TransferToAddress` and unwrapping events and the `ObjectID` of the parent for
+/// `TransferToObject` events; it is ignored for the other kinds of events.
+/// All the natives reading recipients go through this function, so that the recipient of an
+/// event is interpreted in the same way everywhere. Returns a `PartialVMError` if `recipient`
+/// does not have the length expected for the kind of event.
+fn parse_recipient(event_type_byte: u64, recipient: &[u8]) -> PartialVMResult<Option<Owner>> {
+    let invalid_recipient = |expected: &str| {
+        malformed_event_log(format!(
+            "recipient of a {} event is not a valid {} ({} bytes)",
+            event_type_name(event_type_byte),
+            expected,
+            recipient.len()
+        ))
+    };
+    let address = || SuiAddress::try_from(recipient).map_err(|_| invalid_recipient("address"));
+    if event_type_byte == UNWRAPPED_OBJECT_EVENT {
+        return Ok(Some(Owner::AddressOwner(address()?)));
+    }
+    if !is_transfer_event(event_type_byte) {
+        return Ok(None);
+    }
+    match parse_event_type(event_type_byte)? {
+        EventType::TransferToAddress => Ok(Some(Owner::AddressOwner(address()?))),
+        EventType::TransferToObject => {
+            let parent =
+                ObjectID::try_from(recipient).map_err(|_| invalid_recipient("object ID"))?;
+            Ok(Some(object_owner(parent)))
         }
+        EventType::FreezeObject => Ok(Some(Owner::Immutable)),
+        EventType::ShareObject => Ok(Some(Owner::Shared)),
+        _ => Ok(None),
+    }
+}
+
+/// Return a human-readable name for the kind of event described by `event_type_byte`.
+fn event_type_name(event_type_byte: u64) -> &'static str {
+    match event_type_byte {
+        WRAPPED_OBJECT_EVENT => "WrapObject",
+        UPDATE_OBJECT_EVENT => "UpdateObject",
+        UNWRAPPED_OBJECT_EVENT => "UnwrapObject",
+        RESET_EVENT => "Reset",
+        DROPPED_OBJECT_EVENT => "DropObject",
+        LOCKED_SHARED_OBJECT_EVENT => "LockSharedObject",
+        STRICT_DELETE_CHECKING_EVENT => "SetStrictDeleteChecking",
+        SNAPSHOT_EVENT => "SnapshotInventory",
+        RESTORED_SNAPSHOT_EVENT => "RestoreInventory",
+        EVENT_LOG_ID_EVENT => "MarkEventLog",
+        _ => match EventType::try_from_primitive(event_type_byte as u8) {
+            Ok(EventType::TransferToAddress) => "TransferToAddress",
+            Ok(EventType::TransferToObject) => "TransferToObject",
+            Ok(EventType::FreezeObject) => "FreezeObject",
+            Ok(EventType::ShareObject) => "ShareObject",
+            Ok(EventType::DeleteObjectID) => "DeleteObjectID",
+            Ok(EventType::DeleteChildObject) => "DeleteChildObject",
+            Ok(EventType::User) => "User",
+            Err(_) => "Unknown",
+        },
+    }
+}
+
+/// Return the Move value of type `sui::id::ID` for `id`.
+fn id_value(id: ObjectID) -> Value {
+    Value::struct_(Struct::pack(vec![Value::address(id.into())]))
+}
+
+/// Return the Move value of type `OwnerInfo` describing `owner`.
+fn owner_info_value(owner: &Owner) -> Value {
+    let (kind, address) = match owner {
+        Owner::AddressOwner(address) => (ADDRESS_OWNER_KIND, *address),
+        Owner::ObjectOwner(address) => (OBJECT_OWNER_KIND, *address),
+        Owner::Immutable => (IMMUTABLE_OWNER_KIND, SuiAddress::default()),
+        Owner::Shared => (SHARED_OWNER_KIND, SuiAddress::default()),
     };
-    Some(ObjectID::try_from(address.value_as::<AccountAddress>().unwrap().as_slice()).unwrap())
+    Value::struct_(Struct::pack(vec![
+        Value::u8(kind),
+        Value::address(address.into()),
+    ]))
 }
 
 fn account_to_sui_address(address: AccountAddress) -> SuiAddress {
-    SuiAddress::try_from(address.as_slice()).unwrap()
+    // both addresses have the same length, so go through `ObjectID` rather than the fallible
+    // byte slice conversion
+    ObjectID::from(address).into()
+}
+
+/// Return the owner of the children of the object `parent_id`. `Owner::ObjectOwner` stores its
+/// parent as a `SuiAddress` while the inventory is keyed by `ObjectID`; both hold the same bytes,
+/// and this function and `parent_id_of` are the only places converting between the two.
+fn object_owner(parent_id: ObjectID) -> Owner {
+    Owner::ObjectOwner(parent_id.into())
+}
+
+/// Return the ID of the parent object of `owner`, if it is an `Owner::ObjectOwner`.
+fn parent_id_of(owner: &Owner) -> Option<ObjectID> {
+    match owner {
+        Owner::ObjectOwner(parent) => Some(ObjectID::from(*parent)),
+        _ => None,
+    }
+}
+
+/// Return the `Owner` described by `owner_kind`. `address` is only used for the owned variants.
+fn owner_from_kind(owner_kind: u8, address: AccountAddress) -> Option<Owner> {
+    match owner_kind {
+        ADDRESS_OWNER_KIND => Some(Owner::AddressOwner(account_to_sui_address(address))),
+        OBJECT_OWNER_KIND => Some(object_owner(ObjectID::from(address))),
+        IMMUTABLE_OWNER_KIND => Some(Owner::Immutable),
+        SHARED_OWNER_KIND => Some(Owner::Shared),
+        _ => None,
+    }
+}
+
+/// Return the cost of a native that processes `num_events` events of the log: a base cost plus
+/// one increment per event, so that helpers running over long logs are visibly more expensive.
+/// This only depends on the size of the log, not on how much of it was replayed from a checkpoint,
+/// so the cost is deterministic.
+fn replay_cost(context: &NativeContext, num_events: usize) -> InternalGasUnits<GasCarrier> {
+    // Gas amount doesn't matter much as this is test only, but it should scale with the work done.
+    native_gas(
+        context.cost_table(),
+        NativeCostIndex::EMIT_EVENT,
+        1 + num_events,
+    )
 }
 
 /// Process the event log to determine the global set of live objects
-/// Returns the abort_code if an error is encountered.
-fn get_global_inventory(events: &[Event]) -> Result<Inventory, u64> {
-    let mut inventory = Inventory::new();
+/// Returns the abort_code if an error is encountered, or a `PartialVMError` if the
+/// event log itself is malformed.
+fn get_global_inventory(events: &[Event]) -> PartialVMResult<Result<Inventory, u64>> {
+    Ok(replay_events(events)?.into_inventory())
+}
+
+/// Process the event log, returning the replay state after the last event.
+/// The replay resumes from the longest checkpoint of the log covering at most `events`, if any,
+/// and the state after the last event is recorded as a new checkpoint.
+fn replay_events(events: &[Event]) -> PartialVMResult<InventoryReplay> {
+    let log_id = match event_log_id(events) {
+        Some(log_id) => log_id,
+        // only the logs marked by `begin` are checkpointed
+        None => return InventoryReplay::default().replay(events),
+    };
+    let replay = INVENTORY_CHECKPOINTS
+        .with(|checkpoints| checkpoints.borrow_mut().resume(log_id, events))?
+        .unwrap_or_default();
+    if replay.num_events == events.len() {
+        return Ok(replay);
+    }
+    let replay = replay.replay(&events[replay.num_events..])?;
+    let checkpoint = InventoryCheckpoint::new(events, &replay)?;
+    INVENTORY_CHECKPOINTS.with(|checkpoints| checkpoints.borrow_mut().save(checkpoint));
+    Ok(replay)
+}
+
+/// Return the identifier of the event log held by the first `EVENT_LOG_ID_EVENT` of `events`, if
+/// any.
+fn event_log_id(events: &[Event]) -> Option<u64> {
+    events.iter().find_map(|(_, event_type_byte, _, _, val)| {
+        if *event_type_byte == EVENT_LOG_ID_EVENT {
+            val.copy_value().and_then(|v| v.value_as::<u64>()).ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Maximum number of replay checkpoints kept per thread.
+const MAX_INVENTORY_CHECKPOINTS: usize = 8;
+
+/// Identifier of the next event log marked by `mark_event_log_internal`. Identifiers are never
+/// reused within the process, so the checkpoints of a log are never resumed on another one.
+static NEXT_EVENT_LOG_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A copy of the replay state after processing the first `replay.num_events` events of a log.
+struct InventoryCheckpoint {
+    /// Copy of the last event processed. Events are only ever appended to a log, but a log that
+    /// no longer has this event at the same position has been truncated since, and the
+    /// checkpoint is not resumed.
+    last_event: Option<(Vec<u8>, u64, Type, Value)>,
+    replay: InventoryReplay,
+}
+
+impl InventoryCheckpoint {
+    fn new(events: &[Event], replay: &InventoryReplay) -> PartialVMResult<Self> {
+        let last_event = match events[..replay.num_events].last() {
+            Some((recipient, event_type_byte, type_, _, val)) => Some((
+                recipient.clone(),
+                *event_type_byte,
+                type_.clone(),
+                val.copy_value()?,
+            )),
+            None => None,
+        };
+        Ok(Self {
+            last_event,
+            replay: replay.copy()?,
+        })
+    }
+
+    /// Return whether this checkpoint was built from a prefix of `events`, which must be a prefix
+    /// of the log the checkpoint belongs to.
+    fn is_prefix_of(&self, events: &[Event]) -> PartialVMResult<bool> {
+        let num_events = self.replay.num_events;
+        if num_events > events.len() {
+            return Ok(false);
+        }
+        match (&self.last_event, num_events.checked_sub(1)) {
+            (Some((recipient, event_type_byte, type_, val)), Some(last)) => {
+                let (cur_recipient, cur_event_type_byte, cur_type, _, cur_val) = &events[last];
+                Ok(recipient == cur_recipient
+                    && event_type_byte == cur_event_type_byte
+                    && type_ == cur_type
+                    && val.equals(cur_val)?)
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+/// Replay checkpoints of a single event log, keyed by the number of events they cover.
+#[derive(Default)]
+struct InventoryCheckpoints {
+    /// Identifier held by the `EVENT_LOG_ID_EVENT` of the log the checkpoints belong to.
+    log_id: Option<u64>,
+    checkpoints: BTreeMap<usize, InventoryCheckpoint>,
+}
+
+thread_local! {
+    /// Checkpoints of the event log replayed last on this thread. Every inventory query otherwise
+    /// replays the log from the start, which is quadratic in the number of transactions of a
+    /// scenario.
+    static INVENTORY_CHECKPOINTS: RefCell<InventoryCheckpoints> =
+        RefCell::new(InventoryCheckpoints::default());
+}
+
+impl InventoryCheckpoints {
+    /// Return a copy of the state of the longest checkpointed prefix of `events`, a prefix of the
+    /// log identified by `log_id`, or None if there is none. Checkpoints of another log are
+    /// dropped, as are the checkpoints invalidated by a truncation of the log.
+    fn resume(
+        &mut self,
+        log_id: u64,
+        events: &[Event],
+    ) -> PartialVMResult<Option<InventoryReplay>> {
+        if self.log_id != Some(log_id) {
+            self.log_id = Some(log_id);
+            self.checkpoints.clear();
+        }
+        while let Some((num_events, checkpoint)) =
+            self.checkpoints.range(..=events.len()).next_back()
+        {
+            if checkpoint.is_prefix_of(events)? {
+                return Ok(Some(checkpoint.replay.copy()?));
+            }
+            // the log was truncated before this checkpoint, so it is gone with the events it covers
+            let num_events = *num_events;
+            self.checkpoints.retain(|covered, _| *covered < num_events);
+        }
+        Ok(None)
+    }
+
+    /// Record `checkpoint` of the log the checkpoints were last resumed for, evicting the
+    /// checkpoint covering the fewest events if there are too many.
+    fn save(&mut self, checkpoint: InventoryCheckpoint) {
+        self.checkpoints
+            .insert(checkpoint.replay.num_events, checkpoint);
+        while self.checkpoints.len() > MAX_INVENTORY_CHECKPOINTS {
+            let smallest = *self.checkpoints.keys().next().unwrap();
+            self.checkpoints.remove(&smallest);
+        }
+    }
+}
+
+/// State of the inventory after processing a prefix of the event log.
+#[derive(Default)]
+struct InventoryReplay {
+    inventory: Inventory,
     // Since we allow transfer object to ID, it's possible that when we transfer
     // an object to a parenet object, the parent object does not yet exist in the event log.
     // And without the parent object we cannot know the ultimate signer.
@@ -91,92 +560,282 @@ fn get_global_inventory(events: &[Event]) -> Result<Inventory, u64> {
     // to the unresolved_signer_parents map, which maps from parent object ID
     // to the list of child objects it has. Whenever a new object is seen, we check the map
     // and resolve if the object is an unresolved parent.
-    let mut unresolved_signer_parents: BTreeMap<ObjectID, BTreeSet<ObjectID>> = BTreeMap::new();
-    for (recipient, event_type_byte, type_, _layout, val) in events {
-        let obj_id = if let Some(obj_id) = get_object_id_from_event(*event_type_byte, val) {
+    unresolved_signer_parents: BTreeMap<ObjectID, BTreeSet<ObjectID>>,
+    /// Objects that are currently wrapped, i.e. that were wrapped and have not been unwrapped,
+    /// transferred or deleted since. They are not part of `inventory`.
+    wrapped: BTreeSet<ObjectID>,
+    /// Whether `DeleteObjectID` events for objects that are no longer live are recorded in
+    /// `delete_anomalies`.
+    strict_delete_checking: bool,
+    /// Index in the log of the first event bringing each object into the inventory, for all the
+    /// objects that have been in the inventory at some point. This gives the creation order of
+    /// objects, and tells the deletion of an object that is gone apart from the deletion of an ID
+    /// that was never used by an object.
+    first_seen: BTreeMap<ObjectID, usize>,
+    /// IDs of the objects deleted while no longer live, in strict delete checking mode.
+    delete_anomalies: Vec<ObjectID>,
+    /// State at each `SNAPSHOT_EVENT` processed so far, keyed by the index of the marker in the
+    /// log. The snapshots themselves have no snapshots.
+    snapshots: BTreeMap<usize, InventoryReplay>,
+    /// Number of events of the log processed so far.
+    num_events: usize,
+}
+
+impl OwnedObj {
+    fn copy(&self) -> PartialVMResult<Self> {
+        Ok(Self {
+            value: self.value.copy_value()?,
+            type_: self.type_.clone(),
+            owner: self.owner,
+            signer: self.signer,
+            version: self.version,
+            layout: self.layout.clone(),
+        })
+    }
+}
+
+impl InventoryReplay {
+    fn copy(&self) -> PartialVMResult<Self> {
+        Ok(Self {
+            inventory: self
+                .inventory
+                .iter()
+                .map(|(id, obj)| Ok((*id, obj.copy()?)))
+                .collect::<PartialVMResult<_>>()?,
+            unresolved_signer_parents: self.unresolved_signer_parents.clone(),
+            wrapped: self.wrapped.clone(),
+            strict_delete_checking: self.strict_delete_checking,
+            first_seen: self.first_seen.clone(),
+            delete_anomalies: self.delete_anomalies.clone(),
+            snapshots: self
+                .snapshots
+                .iter()
+                .map(|(index, snapshot)| Ok((*index, snapshot.copy()?)))
+                .collect::<PartialVMResult<_>>()?,
+            num_events: self.num_events,
+        })
+    }
+
+    /// Return the abort_code if the replayed events are not consistent.
+    fn check_consistent(&self) -> Result<(), u64> {
+        if self.unresolved_signer_parents.is_empty() {
+            Ok(())
+        } else {
+            Err(EPARENT_OBJECT_NOT_FOUND)
+        }
+    }
+
+    /// Return the inventory, or the abort_code if the replayed events are not consistent.
+    fn into_inventory(self) -> Result<Inventory, u64> {
+        self.check_consistent().map(|_| self.inventory)
+    }
+
+    /// Apply `events`, the next events of the log, to the inventory.
+    fn replay(mut self, events: &[Event]) -> PartialVMResult<Self> {
+        for event in events {
+            self.process_event(event)?;
+        }
+        Ok(self)
+    }
+
+    /// Apply the next event of the log to the inventory.
+    fn process_event(&mut self, event: &Event) -> PartialVMResult<()> {
+        let (recipient, event_type_byte, type_, layout, val) = event;
+        self.num_events += 1;
+
+        if *event_type_byte == RESET_EVENT {
+            // special, test_scenario-only event clearing all state accumulated so far
+            self.inventory.clear();
+            self.unresolved_signer_parents.clear();
+            self.wrapped.clear();
+            self.first_seen.clear();
+            self.delete_anomalies.clear();
+            return Ok(());
+        }
+        if *event_type_byte == STRICT_DELETE_CHECKING_EVENT {
+            self.strict_delete_checking = val.copy_value()?.value_as::<bool>()?;
+            return Ok(());
+        }
+        if *event_type_byte == SNAPSHOT_EVENT {
+            let snapshots = std::mem::take(&mut self.snapshots);
+            let snapshot = self.copy()?;
+            self.snapshots = snapshots;
+            self.snapshots.insert(self.num_events - 1, snapshot);
+            return Ok(());
+        }
+        if *event_type_byte == RESTORED_SNAPSHOT_EVENT {
+            // special, test_scenario-only event bringing the state back to an earlier snapshot.
+            // Snapshots taken since then remain available
+            let index = val.copy_value()?.value_as::<u64>()? as usize;
+            let snapshot = match self.snapshots.get(&index) {
+                Some(snapshot) => snapshot.copy()?,
+                None => {
+                    return Err(malformed_event_log(format!(
+                        "no inventory snapshot at index {}",
+                        index
+                    )))
+                }
+            };
+            let snapshots = std::mem::take(&mut self.snapshots);
+            let num_events = self.num_events;
+            *self = snapshot;
+            self.snapshots = snapshots;
+            self.num_events = num_events;
+            return Ok(());
+        }
+
+        let obj_id = if let Some(obj_id) = get_object_id_from_event(*event_type_byte, val)? {
             obj_id
         } else {
-            continue;
+            return Ok(());
         };
 
-        if *event_type_byte == WRAPPED_OBJECT_EVENT {
-            // special, test_scenario-only event for object wrapping. treat the same as DeleteObjectID for inventory purposes--a wrapped object is not available for use
-            assert!(inventory.remove(&obj_id).is_some());
-            continue;
-        }
-        if *event_type_byte == UPDATE_OBJECT_EVENT {
-            if let Some(cur) = inventory.get_mut(&obj_id) {
-                let new_value = val.copy_value().unwrap();
-                // Update the object content since it may have been mutated.
-                cur.value = new_value;
+        match *event_type_byte {
+            WRAPPED_OBJECT_EVENT => {
+                // special, test_scenario-only event for object wrapping. treat the same as DeleteObjectID for inventory purposes--a wrapped object is not available for use
+                if self.inventory.remove(&obj_id).is_none() {
+                    return Err(malformed_event_log(format!(
+                        "wrapped object {} is not in the inventory",
+                        obj_id
+                    )));
+                }
+                self.wrapped.insert(obj_id);
             }
-            continue;
-        }
-        let event_type = EventType::try_from_primitive(*event_type_byte as u8)
-            .expect("This will always succeed for a well-structured event log");
-        match event_type {
-            EventType::TransferToAddress
-            | EventType::TransferToObject
-            | EventType::FreezeObject
-            | EventType::ShareObject => {
-                let owner = get_new_owner(&event_type, recipient.clone());
-                let signer = if event_type == EventType::TransferToObject {
-                    let parent_id = ObjectID::try_from(recipient.as_slice()).unwrap();
-                    if let Some(parent_obj) = inventory.get(&parent_id) {
-                        parent_obj.signer
-                    } else {
-                        unresolved_signer_parents
-                            .entry(parent_id)
-                            .or_default()
-                            .insert(obj_id);
-                        None
-                    }
-                } else {
-                    Some(owner)
-                };
-                if signer.is_some() {
-                    if let Some(children) = unresolved_signer_parents.remove(&obj_id) {
-                        for child in children {
-                            inventory.get_mut(&child).unwrap().signer = signer;
-                        }
+            DROPPED_OBJECT_EVENT => {
+                // special, test_scenario-only event making an object unavailable without deleting it
+                self.inventory.remove(&obj_id);
+                self.wrapped.remove(&obj_id);
+            }
+            UNWRAPPED_OBJECT_EVENT => {
+                // special, test_scenario-only event for object unwrapping. the object becomes available again, owned by the address recorded in the event
+                if let Some(owner) = parse_recipient(*event_type_byte, recipient)? {
+                    self.insert_object(obj_id, owner, type_, layout, val)?;
+                }
+            }
+            UPDATE_OBJECT_EVENT => {
+                if let Some(cur) = self.inventory.get_mut(&obj_id) {
+                    let new_value = val.copy_value()?;
+                    // Update the object content since it may have been mutated.
+                    cur.version = get_object_version(new_value.copy_value()?)?;
+                    cur.value = new_value;
+                    // keep the layout in sync with the value so the object can be re-serialized
+                    cur.layout = layout.clone();
+                }
+            }
+            _ => match parse_event_type(*event_type_byte)? {
+                event_type @ (EventType::TransferToAddress
+                | EventType::TransferToObject
+                | EventType::FreezeObject
+                | EventType::ShareObject) => {
+                    let owner = get_new_owner(&event_type, recipient)?;
+                    self.insert_object(obj_id, owner, type_, layout, val)?;
+                }
+                event_type @ (EventType::DeleteObjectID | EventType::DeleteChildObject) => {
+                    // note: obj_id may or may not be present in `inventory`--a useer can create an ID and delete it without associating it with a transferred object
+                    let was_live = self.inventory.remove(&obj_id).is_some();
+                    let was_wrapped = self.wrapped.remove(&obj_id);
+                    if self.strict_delete_checking
+                        && event_type == EventType::DeleteObjectID
+                        && !was_live
+                        && !was_wrapped
+                        && self.first_seen.contains_key(&obj_id)
+                    {
+                        self.delete_anomalies.push(obj_id);
                     }
                 }
-                // note; may overwrite older values of the object, which is intended
-                inventory.insert(
-                    obj_id,
-                    OwnedObj {
-                        value: Value::copy_value(val).unwrap(),
-                        type_: type_.clone(),
-                        owner,
-                        signer,
-                    },
-                );
+                EventType::User => (),
+            },
+        }
+        Ok(())
+    }
+
+    /// Insert the object `val` of type `type_` into the inventory with the given `owner`,
+    /// resolving its signer as well as the signer of any of its children waiting for it.
+    fn insert_object(
+        &mut self,
+        obj_id: ObjectID,
+        owner: Owner,
+        type_: &Type,
+        layout: &MoveTypeLayout,
+        val: &Value,
+    ) -> PartialVMResult<()> {
+        let signer = if let Some(parent_id) = parent_id_of(&owner) {
+            if let Some(parent_obj) = self.inventory.get(&parent_id) {
+                parent_obj.signer
+            } else {
+                self.unresolved_signer_parents
+                    .entry(parent_id)
+                    .or_default()
+                    .insert(obj_id);
+                None
             }
-            EventType::DeleteObjectID | EventType::DeleteChildObject => {
-                // note: obj_id may or may not be present in `inventory`--a useer can create an ID and delete it without associating it with a transferred object
-                inventory.remove(&obj_id);
+        } else {
+            Some(owner)
+        };
+        if signer.is_some() {
+            if let Some(children) = self.unresolved_signer_parents.remove(&obj_id) {
+                for child in children {
+                    self.inventory
+                        .get_mut(&child)
+                        .ok_or_else(|| {
+                            malformed_event_log(format!(
+                                "child object {} is not in the inventory",
+                                child
+                            ))
+                        })?
+                        .signer = signer;
+                    self.propagate_signer(child, signer);
+                }
             }
-            EventType::User => (),
         }
+        let previous_signer = self.inventory.get(&obj_id).map(|obj| obj.signer);
+        // an object re-entering the inventory is no longer wrapped
+        self.wrapped.remove(&obj_id);
+        // `num_events` already accounts for the event being processed
+        self.first_seen.entry(obj_id).or_insert(self.num_events - 1);
+        // note; may overwrite older values of the object, which is intended: the owner of an
+        // object that is transferred several times (e.g. A -> B -> A) is the one of the last event
+        self.inventory.insert(
+            obj_id,
+            OwnedObj {
+                value: val.copy_value()?,
+                type_: type_.clone(),
+                owner,
+                signer,
+                version: get_object_version(val.copy_value()?)?,
+                layout: layout.clone(),
+            },
+        );
+        // a re-transferred object takes its descendants along, so they must follow its new signer
+        if matches!(previous_signer, Some(previous) if previous != signer) {
+            self.propagate_signer(obj_id, signer);
+        }
+        Ok(())
     }
-    if unresolved_signer_parents.is_empty() {
-        Ok(inventory)
-    } else {
-        Err(EPARENT_OBJECT_NOT_FOUND)
+
+    /// Set the signer of all objects transitively owned by the object `parent_id` to `signer`.
+    fn propagate_signer(&mut self, parent_id: ObjectID, signer: Option<Owner>) {
+        let mut to_visit = vec![parent_id];
+        while let Some(parent_id) = to_visit.pop() {
+            let parent = object_owner(parent_id);
+            for (id, obj) in self.inventory.iter_mut() {
+                if obj.owner == parent {
+                    obj.signer = signer;
+                    to_visit.push(*id);
+                }
+            }
+        }
     }
 }
 
 /// Return the new owner of the object after the transfer event.
-fn get_new_owner(event_type: &EventType, recipient: Vec<u8>) -> Owner {
-    match event_type {
-        EventType::FreezeObject => Owner::Immutable,
-        EventType::ShareObject => Owner::Shared,
-        EventType::TransferToAddress => {
-            Owner::AddressOwner(SuiAddress::try_from(recipient).unwrap())
-        }
-        EventType::TransferToObject => Owner::ObjectOwner(SuiAddress::try_from(recipient).unwrap()),
-        _ => panic!("Unrecognized event_type"),
-    }
+/// Frozen objects are immutable (`Owner::Immutable`), while shared objects stay mutable
+/// (`Owner::Shared`); the two are never conflated, so natives can tell them apart.
+/// Returns a `PartialVMError` rather than panicking if `recipient` is not a valid address or
+/// `event_type` is not a transfer, so that a malformed event fails the test instead of the runner.
+fn get_new_owner(event_type: &EventType, recipient: &[u8]) -> PartialVMResult<Owner> {
+    parse_recipient(*event_type as u64, recipient)?
+        .ok_or_else(|| malformed_event_log("event does not transfer an object".to_string()))
 }
 
 /// Get the objects of type `type_` that can be spent by `addr`
@@ -187,23 +846,69 @@ fn get_inventory_for(
     type_: &Type,
     tx_end_index: usize,
     events: &[Event],
-) -> Result<Vec<Value>, u64> {
-    let inventory = get_global_inventory(&events[..tx_end_index])?;
-    Ok(inventory
+) -> PartialVMResult<Result<Vec<Value>, u64>> {
+    let inventory = match get_global_inventory(&events[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(Err(abort_code)),
+    };
+    Ok(Ok(inventory
         .into_iter()
-        .filter(|(_, obj)| {
-            &obj.type_ == type_
-                && if let Some(parent) = parent_object {
-                    let obj_signer = obj.signer.unwrap();
-                    obj.owner
-                        == Owner::ObjectOwner(SuiAddress::try_from(parent.as_slice()).unwrap())
-                        && (!obj_signer.is_owned() || obj_signer == signer)
-                } else {
-                    obj.owner == signer
-                }
-        })
+        .filter(|(_, obj)| can_be_spent_by(obj, signer, parent_object, type_))
         .map(|(_, obj)| obj.value)
-        .collect())
+        .collect()))
+}
+
+/// Return whether `obj` is of type `type_` and can be spent by `signer`, either directly or,
+/// if `parent_object` is set, as a child of `parent_object`
+fn can_be_spent_by(
+    obj: &OwnedObj,
+    signer: Owner,
+    parent_object: Option<AccountAddress>,
+    type_: &Type,
+) -> bool {
+    &obj.type_ == type_
+        && if let Some(parent) = parent_object {
+            let obj_signer = obj.signer.unwrap();
+            obj.owner == object_owner(ObjectID::from(parent))
+                && (!obj_signer.is_owned() || obj_signer == signer)
+        } else {
+            obj.owner == signer
+        }
+}
+
+/// Index the objects in `inventory` owned by another object by the ID of their parent.
+/// The children of each parent are listed in increasing order of ID.
+fn children_index(inventory: &Inventory) -> BTreeMap<ObjectID, Vec<ObjectID>> {
+    let mut children: BTreeMap<ObjectID, Vec<ObjectID>> = BTreeMap::new();
+    for (id, obj) in inventory {
+        if let Some(parent_id) = parent_id_of(&obj.owner) {
+            children.entry(parent_id).or_default().push(*id);
+        }
+    }
+    children
+}
+
+/// Return the IDs of all objects in `inventory` that `owner` owns either directly or indirectly,
+/// i.e. through a chain of `Owner::ObjectOwner` edges starting at an object `owner` owns directly
+fn transitively_owned_ids(inventory: &Inventory, owner: SuiAddress) -> BTreeSet<ObjectID> {
+    let children = children_index(inventory);
+    let mut to_visit: Vec<ObjectID> = inventory
+        .iter()
+        .filter(|(_, obj)| obj.owner == Owner::AddressOwner(owner))
+        .map(|(id, _)| *id)
+        .collect();
+    // ownership cycles cannot be created through the normal transfer flow, but keep track of
+    // the visited objects anyway so that a malformed event log cannot make this loop forever
+    let mut visited = BTreeSet::new();
+    while let Some(id) = to_visit.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(ids) = children.get(&id) {
+            to_visit.extend(ids);
+        }
+    }
+    visited
 }
 
 pub fn emit_wrapped_object_events(
@@ -225,12 +930,12 @@ pub fn emit_wrapped_object_events(
         removed_ids.insert(id_bytes.value_as::<AccountAddress>()?.into());
     }
 
-    let processed_ids: BTreeSet<_> = context.events()[tx_begin_idx..]
+    let processed_ids = context.events()[tx_begin_idx..]
         .iter()
         .filter_map(|(_, event_type_byte, _, _, val)| {
-            get_object_id_from_event(*event_type_byte, val)
+            get_object_id_from_event(*event_type_byte, val).transpose()
         })
-        .collect();
+        .collect::<PartialVMResult<BTreeSet<_>>>()?;
     // Any object that was removed (and not returned) during the current transaction,
     // but did not appear in any of the events, must be wrapped.
     for id in removed_ids.difference(&processed_ids) {
@@ -242,7 +947,7 @@ pub fn emit_wrapped_object_events(
         )?;
     }
 
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
     Ok(NativeResult::ok(cost, smallvec![]))
 }
 
@@ -277,14 +982,14 @@ pub fn get_account_owned_inventory(
     let tx_end_index = pop_arg!(args, u64) as usize;
     let owner_address = pop_arg!(args, AccountAddress);
 
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let cost = replay_cost(context, tx_end_index);
     match get_inventory_for(
         Owner::AddressOwner(account_to_sui_address(owner_address)),
         None,
         &ty_args[0],
         tx_end_index,
         context.events(),
-    ) {
+    )? {
         Ok(inventory) => Ok(NativeResult::ok(
             cost,
             smallvec![Value::vector_for_testing_only(inventory)],
@@ -309,8 +1014,8 @@ pub fn get_unowned_inventory(
         Owner::Shared
     };
 
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
-    match get_inventory_for(owner, None, &ty_args[0], tx_end_index, context.events()) {
+    let cost = replay_cost(context, tx_end_index);
+    match get_inventory_for(owner, None, &ty_args[0], tx_end_index, context.events())? {
         Ok(inventory) => Ok(NativeResult::ok(
             cost,
             smallvec![Value::vector_for_testing_only(inventory)],
@@ -331,14 +1036,14 @@ pub fn get_object_owned_inventory(
     let parent_object = pop_arg!(args, AccountAddress);
     let signer_address = pop_arg!(args, AccountAddress);
 
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let cost = replay_cost(context, tx_end_index);
     match get_inventory_for(
         Owner::AddressOwner(account_to_sui_address(signer_address)),
         Some(parent_object),
         &ty_args[0],
         tx_end_index,
         context.events(),
-    ) {
+    )? {
         Ok(inventory) => Ok(NativeResult::ok(
             cost,
             smallvec![Value::vector_for_testing_only(inventory)],
@@ -347,37 +1052,3400 @@ pub fn get_object_owned_inventory(
     }
 }
 
-/// Delete the given object
-pub fn drop_object_for_testing(
+/// Return the number of values of type `T` in the inventory of `owner_address`, without copying
+/// the values themselves
+pub fn count_inventory(
     context: &mut NativeContext,
     ty_args: Vec<Type>,
-    args: VecDeque<Value>,
+    mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
     debug_assert_eq!(ty_args.len(), 1);
-    debug_assert_eq!(args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
 
-    // Gas amount doesn't matter as this is test only.
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
-    Ok(NativeResult::ok(cost, smallvec![]))
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
+    let signer = Owner::AddressOwner(account_to_sui_address(owner_address));
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let count = inventory
+                .values()
+                .filter(|obj| can_be_spent_by(obj, signer, None, &ty_args[0]))
+                .count();
+            Ok(NativeResult::ok(cost, smallvec![Value::u64(count as u64)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
 }
 
-pub fn update_object(
+/// Return all the values of type `T` that `owner_address` owns either directly or through a chain
+/// of object ownership
+pub fn get_inventory_transitive(
     context: &mut NativeContext,
-    mut ty_args: Vec<Type>,
+    ty_args: Vec<Type>,
     mut args: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
     debug_assert_eq!(ty_args.len(), 1);
-    debug_assert_eq!(args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
 
-    let ty = ty_args.pop().unwrap();
-    let obj = args.pop_back().unwrap();
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
 
-    // Gas amount doesn't matter as this is test only.
-    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
-    context.save_event(vec![], UPDATE_OBJECT_EVENT, ty, obj)?;
-    // Run through the events to make sure the object we returned didn't violate any rules.
-    match get_global_inventory(context.events()) {
-        Ok(_) => Ok(NativeResult::ok(cost, smallvec![])),
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let owned_ids =
+                transitively_owned_ids(&inventory, account_to_sui_address(owner_address));
+            let objects = inventory
+                .into_iter()
+                .filter(|(id, obj)| obj.type_ == ty_args[0] && owned_ids.contains(id))
+                .map(|(_, obj)| obj.value);
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(objects)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the IDs of all the values of type `T` whose owner is exactly the `Owner` described by
+/// `owner_kind` and `owner_address`
+pub fn get_inventory_by_owner_kind(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let owner_kind = pop_arg!(args, u8);
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
+
+    let cost = replay_cost(context, tx_end_index);
+    let owner = match owner_from_kind(owner_kind, owner_address) {
+        Some(owner) => owner,
+        None => return Ok(NativeResult::err(cost, EINVALID_OWNER_KIND)),
+    };
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let ids = inventory
+                .into_iter()
+                .filter(|(_, obj)| obj.type_ == ty_args[0] && obj.owner == owner)
+                .map(|(id, _)| id_value(id));
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(ids)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return whether an object with the given ID is live in the inventory, regardless of its type or owner
+pub fn object_exists_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => Ok(NativeResult::ok(
+            cost,
+            smallvec![Value::bool(inventory.contains_key(&object_id))],
+        )),
         Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
     }
 }
+
+/// Print every live object in the inventory to stderr, along with its type and owner
+pub fn print_inventory(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let cost = replay_cost(context, context.events().len());
+    let inventory = match get_global_inventory(context.events())? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    eprintln!(
+        "[test_scenario] inventory ({} live objects):",
+        inventory.len()
+    );
+    for (id, obj) in &inventory {
+        let type_tag = context.type_to_type_tag(&obj.type_)?;
+        eprintln!("  {}: {} owned by {}", id, type_tag, obj.owner);
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Print every event emitted at or after `tx_begin_idx` to stderr, one line per event, describing
+/// the kind of event, its recipient and the object involved
+pub fn print_transfer_log(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
+    eprintln!("[test_scenario] event log from index {}:", tx_begin_idx);
+    for (i, (recipient, event_type_byte, _, _, val)) in
+        context.events().iter().enumerate().skip(tx_begin_idx)
+    {
+        let recipient = match parse_recipient(*event_type_byte, recipient) {
+            Ok(Some(Owner::AddressOwner(address))) => address.to_string(),
+            Ok(Some(owner)) => {
+                parent_id_of(&owner).map_or_else(|| "-".to_string(), |id| id.to_string())
+            }
+            _ => "-".to_string(),
+        };
+        let object_id = get_object_id_from_event(*event_type_byte, val)?
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        eprintln!(
+            "  #{}: {} object {} recipient {}",
+            i,
+            event_type_name(*event_type_byte),
+            object_id,
+            recipient
+        );
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Delete the given object
+pub fn drop_object_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Delete `obj` by emitting a `DeleteObjectID` event for its versioned ID, so that the
+/// inventory built by subsequent calls to `get_global_inventory` no longer contains it.
+/// The event is tagged with the object's type rather than `VersionedID` so that the type of
+/// the deleted object can be recovered from the log.
+pub fn delete_object_for_testing(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    let ty = ty_args.pop().unwrap();
+    let obj = args.pop_back().unwrap();
+    // the versioned ID is the first field of every object; this has the layout expected by
+    // `get_object_id_from_event` for `DeleteObjectID` events
+    let versioned_id = get_nth_struct_field(obj, 0)?;
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    if !context.save_event(vec![], EventType::DeleteObjectID as u64, ty, versioned_id)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+pub fn update_object(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    let ty = ty_args.pop().unwrap();
+    let obj = args.pop_back().unwrap();
+
+    let cost = replay_cost(context, context.events().len());
+    context.save_event(vec![], UPDATE_OBJECT_EVENT, ty, obj)?;
+    // Run through the events to make sure the object we returned didn't violate any rules.
+    match get_global_inventory(context.events())? {
+        Ok(_) => Ok(NativeResult::ok(cost, smallvec![])),
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the ID of the object of type `T` most recently transferred to `owner_address`, as a
+/// vector with zero or one element. Only objects that are still in the inventory of
+/// `owner_address` are considered.
+pub fn most_recent_object_id_for_owner(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
+    let owner = Owner::AddressOwner(account_to_sui_address(owner_address));
+
+    let cost = replay_cost(context, tx_end_index);
+    let events = &context.events()[..tx_end_index];
+    let inventory = match get_global_inventory(events)? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut most_recent = None;
+    for (recipient, event_type_byte, type_, _, val) in events.iter().rev() {
+        if *event_type_byte != EventType::TransferToAddress as u64
+            || type_ != &ty_args[0]
+            || recipient.as_slice() != owner_address.as_slice()
+        {
+            continue;
+        }
+        if let Some(id) = get_object_id_from_event(*event_type_byte, val)? {
+            if inventory.get(&id).map_or(false, |obj| obj.owner == owner) {
+                most_recent = Some(id);
+                break;
+            }
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(
+            most_recent.into_iter().map(id_value)
+        )],
+    ))
+}
+
+/// Return the version of the object with the given ID as recorded in the inventory
+pub fn object_version_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.get(&object_id) {
+        Some(obj) => Ok(NativeResult::ok(
+            cost,
+            smallvec![Value::u64(obj.version.value())],
+        )),
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    }
+}
+
+/// Return the IDs of all immutable objects in the inventory
+pub fn frozen_object_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let ids = inventory
+                .into_iter()
+                .filter(|(_, obj)| obj.owner.is_immutable())
+                .map(|(id, _)| id_value(id));
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(ids)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the value of type `T` with the given ID, regardless of its owner.
+/// Aborts if there is no object with this ID or if it is not of type `T`.
+pub fn take_object_by_id(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let mut inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.remove(&object_id) {
+        Some(obj) if obj.type_ == ty_args[0] => Ok(NativeResult::ok(cost, smallvec![obj.value])),
+        Some(_) => Ok(NativeResult::err(cost, ETYPE_MISMATCH)),
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    }
+}
+
+/// Record that `obj` was extracted from a wrapper and now belongs to `owner`, so that it
+/// re-enters the inventory
+pub fn emit_unwrapped_object_event(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let ty = ty_args.pop().unwrap();
+    let owner = pop_arg!(args, AccountAddress);
+    let obj = args.pop_back().unwrap();
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(owner.to_vec(), UNWRAPPED_OBJECT_EVENT, ty, obj)?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return all the values of type `T` that `signer_address` can access, either directly or as
+/// children of its objects, together with an `OwnerInfo` describing the direct owner of each
+pub fn get_inventory_with_owners(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let signer_address = pop_arg!(args, AccountAddress);
+    let signer = Owner::AddressOwner(account_to_sui_address(signer_address));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let (objects, owners): (Vec<_>, Vec<_>) = inventory
+        .into_iter()
+        .filter(|(_, obj)| obj.type_ == ty_args[0] && obj.signer == Some(signer))
+        .map(|(_, obj)| {
+            let owner = owner_info_value(&obj.owner);
+            (obj.value, owner)
+        })
+        .unzip();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![
+            Value::vector_for_testing_only(objects),
+            Value::vector_for_testing_only(owners)
+        ],
+    ))
+}
+
+/// Abort unless the object with the given ID is in the inventory and owned by `owner_address`
+pub fn assert_object_owned_by(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.get(&object_id).map(|obj| obj.owner) {
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+        Some(Owner::AddressOwner(owner)) if owner == account_to_sui_address(owner_address) => {
+            Ok(NativeResult::ok(cost, smallvec![]))
+        }
+        Some(Owner::AddressOwner(_)) => Ok(NativeResult::err(cost, EWRONG_OWNER)),
+        Some(_) => Ok(NativeResult::err(cost, ENOT_ADDRESS_OWNED)),
+    }
+}
+
+/// Return the current position of the event cursor, i.e., the index that the next event will be
+/// written to. Unlike `num_events`, which counts the events emitted so far, this is intended to be
+/// captured at the start of a logical transaction and later passed as a `tx_begin_idx` to natives
+/// that inspect the events emitted since then. The two happen to coincide today because every
+/// event occupies exactly one slot in the log.
+pub fn last_tx_index(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+
+    let cursor = context.events().len();
+    Ok(NativeResult::ok(cost, smallvec![Value::u64(cursor as u64)]))
+}
+
+/// Return the IDs of the objects transferred to `recipient` at or after the index `tx_begin_idx`
+/// of the global event log that are still owned by `recipient`, in the order of their first
+/// transfer. Objects that were transferred to `recipient` but later deleted, wrapped or
+/// transferred away are not included.
+pub fn transferred_object_ids_to(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let recipient = account_to_sui_address(pop_arg!(args, AccountAddress));
+
+    let cost = replay_cost(context, context.events().len());
+    let events = context.events();
+    let inventory = match get_global_inventory(events)? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+
+    let mut seen = BTreeSet::new();
+    let mut ids = vec![];
+    for (event_recipient, event_type_byte, _, _, val) in events.iter().skip(tx_begin_idx) {
+        if *event_type_byte != EventType::TransferToAddress as u64
+            || event_recipient.as_slice() != recipient.as_ref()
+        {
+            continue;
+        }
+        let obj_id = match get_object_id_from_event(*event_type_byte, val)? {
+            Some(obj_id) => obj_id,
+            None => continue,
+        };
+        let still_owned = matches!(
+            inventory.get(&obj_id).map(|obj| obj.owner),
+            Some(Owner::AddressOwner(owner)) if owner == recipient
+        );
+        if still_owned && seen.insert(obj_id) {
+            ids.push(id_value(obj_id));
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Emit a reset marker. Inventories built from a log containing the marker only reflect the
+/// events after it. Indices into the event log (e.g. `tx_begin_idx`) keep referring to the raw
+/// log, which still contains the events before the marker.
+pub fn scenario_reset(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(vec![], RESET_EVENT, Type::Bool, Value::bool(true))?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the IDs of the objects of type `T` deleted at or after the index `tx_begin_idx` of the
+/// global event log, in the order of deletion.
+/// The type of a deleted object is the type it had in the inventory when it was deleted. Deletion
+/// events for objects that were never in the inventory (e.g. objects created and deleted within
+/// the same transaction) fall back to the type recorded in the event, which is the object type for
+/// `delete_object_for_testing` but only `VersionedID` or `address` for the other deletion paths.
+/// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` if `tx_begin_idx` is beyond the end of the log.
+pub fn typed_deleted_object_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, context.events().len());
+    let events = context.events();
+    if tx_begin_idx > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut replay = InventoryReplay::default();
+    let mut ids = vec![];
+    while replay.num_events < events.len() {
+        let event = &events[replay.num_events];
+        let (_, event_type_byte, event_ty, _, val) = event;
+        let is_delete = *event_type_byte == EventType::DeleteObjectID as u64
+            || *event_type_byte == EventType::DeleteChildObject as u64;
+        if replay.num_events >= tx_begin_idx && is_delete {
+            if let Some(obj_id) = get_object_id_from_event(*event_type_byte, val)? {
+                let deleted_ty = replay
+                    .inventory
+                    .get(&obj_id)
+                    .map_or(event_ty, |obj| &obj.type_);
+                if deleted_ty == &ty_args[0] {
+                    ids.push(id_value(obj_id));
+                }
+            }
+        }
+        replay.process_event(event)?;
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return the shared or immutable object of type `T` with the given ID together with its owner
+/// kind, `SHARED_OWNER_KIND` or `IMMUTABLE_OWNER_KIND`, so that the caller can reject frozen
+/// objects.
+/// Aborts if there is no object with this ID, if it is not of type `T` or if it is owned.
+pub fn take_shared_object_by_id(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let mut inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.remove(&object_id) {
+        Some(obj) if obj.type_ != ty_args[0] => Ok(NativeResult::err(cost, ETYPE_MISMATCH)),
+        Some(obj) if obj.owner.is_shared() => Ok(NativeResult::ok(
+            cost,
+            smallvec![obj.value, Value::u8(SHARED_OWNER_KIND)],
+        )),
+        Some(obj) if obj.owner.is_immutable() => Ok(NativeResult::ok(
+            cost,
+            smallvec![obj.value, Value::u8(IMMUTABLE_OWNER_KIND)],
+        )),
+        Some(_) => Ok(NativeResult::err(cost, ENOT_SHARED)),
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    }
+}
+
+/// Return the number of events emitted whose event type byte is `event_type_byte`. This accepts
+/// both the `EventType` discriminants and the test_scenario-only sentinels such as
+/// `WRAPPED_OBJECT_EVENT`.
+pub fn num_events_of_type(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let event_type_byte = pop_arg!(args, u8) as u64;
+
+    let cost = replay_cost(context, context.events().len());
+
+    let num_events = context
+        .events()
+        .iter()
+        .filter(|(_, cur_event_type_byte, _, _, _)| *cur_event_type_byte == event_type_byte)
+        .count();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(num_events as u64)],
+    ))
+}
+
+/// Return the recipient of the last `TransferToAddress` event emitted at or after the index
+/// `tx_begin_idx` of the global event log, or an empty vector if there is none.
+pub fn most_recent_receiver(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
+
+    let recipient = context
+        .events()
+        .iter()
+        .skip(tx_begin_idx)
+        .rev()
+        .find(|(_, event_type_byte, _, _, _)| {
+            *event_type_byte == EventType::TransferToAddress as u64
+        })
+        .map(|(recipient, _, _, _, _)| recipient.clone())
+        .unwrap_or_default();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(recipient)],
+    ))
+}
+
+/// Return the IDs of the objects of type `T` owned by `owner_address`, plus those of all shared
+/// objects of type `T` if `include_shared` is set. With `include_shared` unset, only objects
+/// directly owned by `owner_address` are considered.
+pub fn get_inventory_filtered(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let include_shared = pop_arg!(args, bool);
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let ids = inventory
+        .into_iter()
+        .filter(|(_, obj)| {
+            obj.type_ == ty_args[0]
+                && (obj.owner == owner || (include_shared && obj.owner.is_shared()))
+        })
+        .map(|(id, _)| id_value(id));
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return whether `owner_address` owns at least one object of type `T`. Unlike
+/// `get_account_owned_inventory`, this stops at the first match and does not copy any object.
+pub fn has_object_of_type(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let found = inventory
+                .values()
+                .any(|obj| can_be_spent_by(obj, owner, None, &ty_args[0]));
+            Ok(NativeResult::ok(cost, smallvec![Value::bool(found)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the BCS serialization of `obj`, the object with ID `object_id`, using its recorded layout.
+fn serialize_object(object_id: ObjectID, obj: &OwnedObj) -> PartialVMResult<Vec<u8>> {
+    obj.value.simple_serialize(&obj.layout).ok_or_else(|| {
+        malformed_event_log(format!(
+            "object {} does not match its recorded layout",
+            object_id
+        ))
+    })
+}
+
+/// Return the size in bytes of the BCS serialization of the object with the given ID
+pub fn object_size_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let bytes = serialize_object(object_id, obj)?;
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(bytes.len() as u64)],
+    ))
+}
+
+/// Return a human-readable, JSON-like description of the object with the given ID: its type,
+/// owner, version and contents. Struct fields are rendered positionally since the recorded
+/// layout does not carry field names.
+pub fn debug_object_json_bytes(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let contents = obj
+        .value
+        .simple_serialize(&obj.layout)
+        .and_then(|bytes| MoveValue::simple_deserialize(&bytes, &obj.layout).ok())
+        .ok_or_else(|| {
+            malformed_event_log(format!(
+                "object {} does not match its recorded layout",
+                object_id
+            ))
+        })?;
+    let json = format!(
+        "{{\"id\": \"{}\", \"type\": \"{}\", \"owner\": \"{}\", \"version\": {}, \"contents\": \"{}\"}}",
+        object_id,
+        context.type_to_type_tag(&obj.type_)?,
+        obj.owner,
+        obj.version.value(),
+        contents
+    );
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(json.into_bytes())],
+    ))
+}
+
+/// Return the IDs of all objects that are currently wrapped, i.e. that were wrapped and have not
+/// been unwrapped, transferred or deleted since
+pub fn wrapped_object_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let replay = replay_events(&context.events()[..tx_end_index])?;
+    if let Err(abort_code) = replay.check_consistent() {
+        return Ok(NativeResult::err(cost, abort_code));
+    }
+    let ids = replay.wrapped.into_iter().map(id_value);
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Emit a `TransferToObject` event making the object `obj` a child of the object with ID
+/// `parent`, without going through `transfer::transfer_to_object`
+pub fn transfer_to_object_internal(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let ty = ty_args.pop().unwrap();
+    let parent = pop_arg!(args, AccountAddress);
+    let obj = args.pop_back().unwrap();
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let obj_id: ObjectID = get_object_id(obj.copy_value()?)?
+        .value_as::<AccountAddress>()?
+        .into();
+    if ObjectID::from(parent) == obj_id {
+        return Ok(NativeResult::err(cost, EINVALID_PARENT));
+    }
+    if !context.save_event(parent.to_vec(), EventType::TransferToObject as u64, ty, obj)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the SHA3-256 digest of the whole inventory. Every object contributes its ID, owner,
+/// type and BCS bytes, in increasing order of ID, so the digest only depends on the live objects.
+pub fn get_inventory_digest(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut hasher = Sha3_256::default();
+    // `Inventory` is a `BTreeMap`, so this iterates in increasing order of ID
+    for (id, obj) in &inventory {
+        let object_bytes = obj.value.simple_serialize(&obj.layout).ok_or_else(|| {
+            malformed_event_log(format!("object {} does not match its recorded layout", id))
+        })?;
+        let type_tag = context.type_to_type_tag(&obj.type_)?;
+        let entry = bcs::to_bytes(&(id, &obj.owner, &type_tag, &object_bytes)).map_err(|e| {
+            PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR)
+                .with_message(format!("cannot serialize object {}: {}", id, e))
+        })?;
+        hasher.update(&entry);
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(hasher.finalize().to_vec())],
+    ))
+}
+
+/// Return the immutable object of type `T` with the given ID.
+/// Aborts if there is no object with this ID, if it is not of type `T` or if it is not immutable.
+pub fn take_immutable_object_by_id(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let mut inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.remove(&object_id) {
+        Some(obj) if obj.type_ != ty_args[0] => Ok(NativeResult::err(cost, ETYPE_MISMATCH)),
+        Some(obj) if obj.owner.is_immutable() => Ok(NativeResult::ok(cost, smallvec![obj.value])),
+        Some(_) => Ok(NativeResult::err(cost, ENOT_IMMUTABLE)),
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    }
+}
+
+/// Return the IDs of all objects live after the first `n` events of the log, in increasing order
+/// of ID. Unlike the natives inspecting a window of events, this reflects the cumulative state.
+pub fn inventory_ids_at(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let n = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, n);
+    let events = context.events();
+    if n > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    match get_global_inventory(&events[..n])? {
+        Ok(inventory) => {
+            let ids = inventory.into_keys().map(id_value);
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(ids)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the number of objects in `inventory` directly owned by `owner`
+fn owned_count(inventory: &Inventory, owner: Owner) -> usize {
+    inventory.values().filter(|obj| obj.owner == owner).count()
+}
+
+/// Return the number of objects of any type directly owned by `owner_address`
+pub fn objects_owned_count(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let count = owned_count(&inventory, owner);
+            Ok(NativeResult::ok(cost, smallvec![Value::u64(count as u64)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the bytes of every address that directly owns at least one object, in increasing order.
+/// Shared, immutable and object-owned objects do not contribute any address.
+pub fn get_distinct_owners(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let owners: BTreeSet<SuiAddress> = inventory
+                .values()
+                .filter_map(|obj| match obj.owner {
+                    Owner::AddressOwner(owner) => Some(owner),
+                    _ => None,
+                })
+                .collect();
+            let owners = owners
+                .into_iter()
+                .map(|owner| Value::vector_u8(owner.to_vec()));
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(owners)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Emit the event changing the owner of the live object with the given ID to the owner described
+/// by `owner_kind` and `owner_address`, using the object's current value from the inventory:
+/// a transfer for the owned kinds, a `FreezeObject` or `ShareObject` event otherwise.
+/// Aborts with `EINVALID_PARENT` if the object would become its own parent.
+pub fn change_owner_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let owner_address = pop_arg!(args, AccountAddress);
+    let owner_kind = pop_arg!(args, u8);
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, context.events().len());
+    let (event_type, recipient) = match owner_kind {
+        ADDRESS_OWNER_KIND => (EventType::TransferToAddress, owner_address.to_vec()),
+        OBJECT_OWNER_KIND => (EventType::TransferToObject, owner_address.to_vec()),
+        IMMUTABLE_OWNER_KIND => (EventType::FreezeObject, vec![]),
+        SHARED_OWNER_KIND => (EventType::ShareObject, vec![]),
+        _ => return Ok(NativeResult::err(cost, EINVALID_OWNER_KIND)),
+    };
+    if owner_kind == OBJECT_OWNER_KIND && ObjectID::from(owner_address) == object_id {
+        return Ok(NativeResult::err(cost, EINVALID_PARENT));
+    }
+    let mut inventory = match get_global_inventory(context.events())? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.remove(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    if !context.save_event(recipient, event_type as u64, obj.type_, obj.value)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Abort unless the object with the given ID was deleted at or after the index `tx_begin_idx` of
+/// the global event log, either by deleting its ID or as a child object
+pub fn assert_deleted_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let object_id = pop_arg!(args, AccountAddress);
+
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
+    for (_, event_type_byte, _, _, val) in context.events().iter().skip(tx_begin_idx) {
+        let deleted_id = if *event_type_byte == EventType::DeleteObjectID as u64 {
+            get_deleted_id_bytes(val)?
+        } else if *event_type_byte == EventType::DeleteChildObject as u64 {
+            val.copy_value()?.value_as::<AccountAddress>()?
+        } else {
+            continue;
+        };
+        if deleted_id == object_id {
+            return Ok(NativeResult::ok(cost, smallvec![]));
+        }
+    }
+    Ok(NativeResult::err(cost, EOBJECT_NOT_DELETED))
+}
+
+/// Abort unless the object with the given ID was wrapped at or after the index `tx_begin_idx` of
+/// the global event log
+pub fn assert_wrapped_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
+    for (_, event_type_byte, _, _, val) in context.events().iter().skip(tx_begin_idx) {
+        if *event_type_byte == WRAPPED_OBJECT_EVENT
+            && get_object_id_from_event(*event_type_byte, val)? == Some(object_id)
+        {
+            return Ok(NativeResult::ok(cost, smallvec![]));
+        }
+    }
+    Ok(NativeResult::err(cost, EOBJECT_NOT_WRAPPED))
+}
+
+/// Return the canonical rendering of the type tag of `type_`, including its type arguments.
+fn type_tag_string(context: &NativeContext, type_: &Type) -> PartialVMResult<String> {
+    // the `Display` implementation of `TypeTag` renders type arguments recursively
+    Ok(context.type_to_type_tag(type_)?.to_string())
+}
+
+/// Return the canonical name of the type of the object with the given ID, e.g.
+/// `0x2::coin::Coin<0x2::sui::SUI>`, including its type arguments
+pub fn object_type_name(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let type_name = type_tag_string(context, &obj.type_)?;
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_u8(type_name.into_bytes())],
+    ))
+}
+
+/// Return, for each type argument, the objects of this type owned by `owner_address`, as one
+/// vector per type argument in the same order. The event log is only replayed once, however many
+/// types are requested. Registered under one Move name per supported number of type arguments.
+pub fn get_inventories(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(!ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut inventories: Vec<Vec<Value>> = ty_args.iter().map(|_| vec![]).collect();
+    for obj in inventory.into_values() {
+        if let Some(i) = ty_args
+            .iter()
+            .position(|type_| can_be_spent_by(&obj, owner, None, type_))
+        {
+            inventories[i].push(obj.value);
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        inventories
+            .into_iter()
+            .map(Value::vector_for_testing_only)
+            .collect(),
+    ))
+}
+
+/// Return the bytes of the IDs of all objects that were given at least two different owners by
+/// the events in `[tx_begin_idx, tx_end_index)`, in increasing order of ID. Replaying such a window
+/// silently keeps the last owner, which can hide accidental double transfers.
+pub fn detect_id_conflicts(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut owners: BTreeMap<ObjectID, Owner> = BTreeMap::new();
+    let mut conflicts = BTreeSet::new();
+    for (recipient, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        let owner = match parse_recipient(*event_type_byte, recipient)? {
+            Some(owner) => owner,
+            None => continue,
+        };
+        let obj_id = match get_object_id_from_event(*event_type_byte, val)? {
+            Some(obj_id) => obj_id,
+            None => continue,
+        };
+        match owners.get(&obj_id) {
+            Some(previous) if *previous != owner => {
+                conflicts.insert(obj_id);
+            }
+            Some(_) => (),
+            None => {
+                owners.insert(obj_id, owner);
+            }
+        }
+    }
+    let conflicts = conflicts
+        .into_iter()
+        .map(|id| Value::vector_u8(id.to_vec()));
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(conflicts)],
+    ))
+}
+
+/// Return the bytes of the IDs of all live objects directly owned by the object with ID
+/// `parent_address`, in increasing order. Grandchildren are not included.
+pub fn get_children(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let parent_id = ObjectID::from(pop_arg!(args, AccountAddress));
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let children = children_index(&inventory)
+                .remove(&parent_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|id| Value::vector_u8(id.to_vec()));
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(children)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the only value of type `T` that `owner_address` can access, aborting with
+/// `ENO_MATCHING_OBJECT` if there is none and `EMULTIPLE_MATCHING_OBJECTS` if there are several.
+pub fn take_one_owned_object(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_address = pop_arg!(args, AccountAddress);
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_inventory_for(
+        Owner::AddressOwner(account_to_sui_address(owner_address)),
+        None,
+        &ty_args[0],
+        tx_end_index,
+        context.events(),
+    )? {
+        Ok(mut inventory) => match inventory.len() {
+            0 => Ok(NativeResult::err(cost, ENO_MATCHING_OBJECT)),
+            1 => Ok(NativeResult::ok(cost, smallvec![inventory.pop().unwrap()])),
+            _ => Ok(NativeResult::err(cost, EMULTIPLE_MATCHING_OBJECTS)),
+        },
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the Move value of type `EventInfo` for each event of type `event_type` in the window
+/// `[tx_begin_idx, tx_end_index)` of the event log.
+pub fn events_of_type_in_window(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let event_type_byte = pop_arg!(args, u8) as u64;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut infos = vec![];
+    for (recipient, cur_event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        if *cur_event_type_byte != event_type_byte {
+            continue;
+        }
+        // `Option<ID>` is a struct wrapping a vector of at most one `ID`
+        let object_id = get_object_id_from_event(event_type_byte, val)?.map(id_value);
+        infos.push(Value::struct_(Struct::pack(vec![
+            Value::vector_u8(recipient.clone()),
+            Value::struct_(Struct::pack(vec![Value::vector_for_testing_only(
+                object_id,
+            )])),
+        ])));
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(infos)],
+    ))
+}
+
+/// Number of objects that entered and left the inventory over a window of the event log
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ObjectBalance {
+    created: usize,
+    deleted: usize,
+}
+
+/// Count the objects created and deleted by `changes`, the event type byte and object ID of each
+/// event of a window, given the IDs of the objects live at the start of the window. Wrapping an
+/// object counts as deleting it and unwrapping it as creating it again, while a reset deletes
+/// every live object. Dropped objects count as deleted too. Transfers of live objects and
+/// deletions of objects that are not live (e.g. an ID deleted without ever being transferred) do
+/// not change the balance.
+/// Restoring a snapshot makes the objects of `restored` live, keyed by the position of the restore
+/// marker in `changes`: the live objects missing from the snapshot count as deleted, and the
+/// objects of the snapshot that are no longer live as created again.
+fn object_balance(
+    mut live: BTreeSet<ObjectID>,
+    changes: &[(u64, Option<ObjectID>)],
+    restored: &BTreeMap<usize, BTreeSet<ObjectID>>,
+) -> ObjectBalance {
+    let mut balance = ObjectBalance::default();
+    for (i, (event_type_byte, obj_id)) in changes.iter().enumerate() {
+        let obj_id = match obj_id {
+            Some(obj_id) => *obj_id,
+            None => {
+                if *event_type_byte == RESET_EVENT {
+                    balance.deleted += live.len();
+                    live.clear();
+                } else if let Some(restored_live) = restored.get(&i) {
+                    balance.deleted += live.difference(restored_live).count();
+                    balance.created += restored_live.difference(&live).count();
+                    live = restored_live.clone();
+                }
+                continue;
+            }
+        };
+        let enters = match *event_type_byte {
+            UNWRAPPED_OBJECT_EVENT => true,
+            WRAPPED_OBJECT_EVENT | DROPPED_OBJECT_EVENT => false,
+            UPDATE_OBJECT_EVENT => continue,
+            _ => match EventType::try_from_primitive(*event_type_byte as u8) {
+                Ok(
+                    EventType::TransferToAddress
+                    | EventType::TransferToObject
+                    | EventType::FreezeObject
+                    | EventType::ShareObject,
+                ) => true,
+                Ok(EventType::DeleteObjectID | EventType::DeleteChildObject) => false,
+                _ => continue,
+            },
+        };
+        if enters && live.insert(obj_id) {
+            balance.created += 1;
+        } else if !enters && live.remove(&obj_id) {
+            balance.deleted += 1;
+        }
+    }
+    balance
+}
+
+/// Abort with `EOBJECTS_NOT_CONSERVED` unless the number of live objects after replaying the log up
+/// to `tx_end_index` equals the number up to `tx_begin_idx`, plus the objects created and minus
+/// the objects deleted by the events in between.
+pub fn assert_no_objects_leaked(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut replay = replay_events(&events[..tx_begin_idx])?;
+    if let Err(abort_code) = replay.check_consistent() {
+        return Ok(NativeResult::err(cost, abort_code));
+    }
+    let live_at_begin: BTreeSet<ObjectID> = replay.inventory.keys().cloned().collect();
+    let mut changes = vec![];
+    // a restore brings back the objects live when its snapshot was taken
+    let mut restored = BTreeMap::new();
+    for (i, event) in events[tx_begin_idx..tx_end_index].iter().enumerate() {
+        let (_, event_type_byte, _, _, val) = event;
+        changes.push((
+            *event_type_byte,
+            get_object_id_from_event(*event_type_byte, val)?,
+        ));
+        replay.process_event(event)?;
+        if *event_type_byte == RESTORED_SNAPSHOT_EVENT {
+            restored.insert(i, replay.inventory.keys().cloned().collect());
+        }
+    }
+    let live_at_end = match replay.into_inventory() {
+        Ok(inventory) => inventory.len(),
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let live_at_begin_count = live_at_begin.len();
+    let balance = object_balance(live_at_begin, &changes, &restored);
+    if live_at_begin_count + balance.created != live_at_end + balance.deleted {
+        return Ok(NativeResult::err(cost, EOBJECTS_NOT_CONSERVED));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the IDs of the objects first transferred, frozen or shared at or after the index
+/// `tx_begin_idx` of the event log, in creation order. An object counts as created by the first
+/// such event about it: objects already involved in any event before `tx_begin_idx`, including
+/// objects unwrapped in the window, are not reported.
+/// Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` if `tx_begin_idx` is beyond the end of the log.
+pub fn created_object_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, context.events().len());
+    let events = context.events();
+    if tx_begin_idx > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+
+    let mut seen = BTreeSet::new();
+    for (_, event_type_byte, _, _, val) in &events[..tx_begin_idx] {
+        if let Some(obj_id) = get_object_id_from_event(*event_type_byte, val)? {
+            seen.insert(obj_id);
+        }
+    }
+    let mut ids = vec![];
+    for (_, event_type_byte, _, _, val) in &events[tx_begin_idx..] {
+        let obj_id = match get_object_id_from_event(*event_type_byte, val)? {
+            Some(obj_id) => obj_id,
+            None => continue,
+        };
+        let is_transfer = is_transfer_event(*event_type_byte);
+        if seen.insert(obj_id) && is_transfer {
+            ids.push(id_value(obj_id));
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Emit the marker removing the object with the given ID from the inventory without deleting it
+pub fn drop_object_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let object_id = pop_arg!(args, AccountAddress);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(
+        vec![],
+        DROPPED_OBJECT_EVENT,
+        Type::Address,
+        Value::address(object_id),
+    )?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the number of distinct objects transferred, frozen or shared by the events in
+/// `[tx_begin_idx, tx_end_index)`, whether or not they are still live.
+pub fn total_distinct_objects(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut ids = BTreeSet::new();
+    for (_, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        if is_transfer_event(*event_type_byte) {
+            ids.extend(get_object_id_from_event(*event_type_byte, val)?);
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(ids.len() as u64)],
+    ))
+}
+
+/// Return the Move value of type `OwnerObjects` for each address directly owning at least one live
+/// object, in increasing order of address. The IDs of each owner are in increasing order too.
+pub fn get_inventory_by_owner(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let mut by_owner: BTreeMap<SuiAddress, Vec<Value>> = BTreeMap::new();
+            for (id, obj) in inventory {
+                if let Owner::AddressOwner(owner) = obj.owner {
+                    by_owner.entry(owner).or_default().push(id_value(id));
+                }
+            }
+            let by_owner = by_owner.into_iter().map(|(owner, ids)| {
+                Value::struct_(Struct::pack(vec![
+                    Value::vector_u8(owner.to_vec()),
+                    Value::vector_for_testing_only(ids),
+                ]))
+            });
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(by_owner)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the owner kind of the live object with the given ID, or `NOT_FOUND_OWNER_KIND` if
+/// there is no such object
+pub fn get_owner_kind(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let kind = match inventory.get(&object_id).map(|obj| obj.owner) {
+                Some(Owner::AddressOwner(_)) => ADDRESS_OWNER_KIND,
+                Some(Owner::ObjectOwner(_)) => OBJECT_OWNER_KIND,
+                Some(Owner::Immutable) => IMMUTABLE_OWNER_KIND,
+                Some(Owner::Shared) => SHARED_OWNER_KIND,
+                None => NOT_FOUND_OWNER_KIND,
+            };
+            Ok(NativeResult::ok(cost, smallvec![Value::u8(kind)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Emit the marker recording that the shared object with the given ID is in use by the current
+/// transaction
+pub fn lock_shared_object_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let object_id = pop_arg!(args, AccountAddress);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(
+        vec![],
+        LOCKED_SHARED_OBJECT_EVENT,
+        Type::Address,
+        Value::address(object_id),
+    )?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Abort with `ESHARED_OBJECT_LOCKED` if the shared object with the given ID was locked at or
+/// after the index `tx_begin_idx` of the event log
+pub fn assert_shared_object_unlocked(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let object_id = pop_arg!(args, AccountAddress);
+
+    let cost = replay_cost(context, context.events().len().saturating_sub(tx_begin_idx));
+    for (_, event_type_byte, _, _, val) in context.events().iter().skip(tx_begin_idx) {
+        if *event_type_byte == LOCKED_SHARED_OBJECT_EVENT
+            && val.copy_value()?.value_as::<AccountAddress>()? == object_id
+        {
+            return Ok(NativeResult::err(cost, ESHARED_OBJECT_LOCKED));
+        }
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the IDs of all the values of type `T` in the inventory, whatever their owner, along with
+/// the `OwnerInfo` describing the direct owner of each
+pub fn all_objects_of_type(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let (ids, owners): (Vec<_>, Vec<_>) = inventory
+        .into_iter()
+        .filter(|(_, obj)| obj.type_ == ty_args[0])
+        .map(|(id, obj)| (id_value(id), owner_info_value(&obj.owner)))
+        .unzip();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![
+            Value::vector_for_testing_only(ids),
+            Value::vector_for_testing_only(owners)
+        ],
+    ))
+}
+
+/// Emit the marker enabling or disabling strict delete checking for the events after it
+pub fn set_strict_delete_checking(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let enabled = pop_arg!(args, bool);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(
+        vec![],
+        STRICT_DELETE_CHECKING_EVENT,
+        Type::Bool,
+        Value::bool(enabled),
+    )?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the IDs of the objects deleted while no longer live by the events replayed in strict
+/// delete checking mode, in deletion order
+pub fn get_delete_anomalies(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let replay = replay_events(&context.events()[..tx_end_index])?;
+    let ids = replay.delete_anomalies.into_iter().map(id_value);
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return the value of type `T` owned by `owner_address` at the index `i` when these values are
+/// sorted by creation order, i.e. by the index of the event that first brought them into the
+/// inventory, aborting with `EOBJECT_INDEX_OUT_OF_RANGE` if there are not enough of them
+pub fn get_object_at_index(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+    let i = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let replay = replay_events(&context.events()[..tx_end_index])?;
+    if let Err(abort_code) = replay.check_consistent() {
+        return Ok(NativeResult::err(cost, abort_code));
+    }
+    let mut objects: Vec<(usize, &OwnedObj)> = replay
+        .inventory
+        .iter()
+        .filter(|(_, obj)| obj.type_ == ty_args[0] && obj.owner == owner)
+        .map(|(id, obj)| (replay.first_seen[id], obj))
+        .collect();
+    objects.sort_by_key(|(first_seen, _)| *first_seen);
+    match objects.get(i) {
+        Some((_, obj)) => Ok(NativeResult::ok(cost, smallvec![obj.value.copy_value()?])),
+        None => Ok(NativeResult::err(cost, EOBJECT_INDEX_OUT_OF_RANGE)),
+    }
+}
+
+/// Freeze `obj` by emitting a `FreezeObject` event for it, so that the inventory built by
+/// subsequent calls to `get_global_inventory` records it as immutable.
+pub fn freeze_object_for_testing(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 1);
+
+    let ty = ty_args.pop().unwrap();
+    let obj = args.pop_back().unwrap();
+    // the replay reads the ID of frozen objects from their versioned ID, so reject values that
+    // do not start with one rather than corrupting the event log
+    get_object_id(obj.copy_value()?)?
+        .value_as::<AccountAddress>()
+        .map_err(|_| {
+            malformed_event_log("frozen object does not start with a VersionedID".to_string())
+        })?;
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    if !context.save_event(vec![], EventType::FreezeObject as u64, ty, obj)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the Move value of type `InventoryDiff` describing the objects created, removed and moved
+/// between the inventories built from the first `before` and `after` events of the log.
+pub fn inventory_diff(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let after = pop_arg!(args, u64) as usize;
+    let before = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, after);
+    let events = context.events();
+    if before > after || after > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let before_inventory = match get_global_inventory(&events[..before])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let after_inventory = match get_global_inventory(&events[..after])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let id_bytes = |id: &ObjectID| Value::vector_u8(id.to_vec());
+    let created: Vec<_> = after_inventory
+        .keys()
+        .filter(|id| !before_inventory.contains_key(id))
+        .map(id_bytes)
+        .collect();
+    let removed: Vec<_> = before_inventory
+        .keys()
+        .filter(|id| !after_inventory.contains_key(id))
+        .map(id_bytes)
+        .collect();
+    let moved: Vec<_> = before_inventory
+        .iter()
+        .filter(|(id, obj)| matches!(after_inventory.get(id), Some(cur) if cur.owner != obj.owner))
+        .map(|(id, _)| id_bytes(id))
+        .collect();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::struct_(Struct::pack(vec![
+            Value::vector_for_testing_only(created),
+            Value::vector_for_testing_only(removed),
+            Value::vector_for_testing_only(moved),
+        ]))],
+    ))
+}
+
+/// Abort with `ETYPE_TAG_MISMATCH` unless the canonical name of the type of the object with the
+/// given ID, as returned by `object_type_name`, is `expected`
+pub fn assert_object_type_tag(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let expected = pop_arg!(args, Vec<u8>);
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    if type_tag_string(context, &obj.type_)?.as_bytes() != expected.as_slice() {
+        return Ok(NativeResult::err(cost, ETYPE_TAG_MISMATCH));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the number of events emitted after the most recent reset marker, or the number of
+/// events emitted if there is none
+pub fn num_events_since_reset(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let num_events = context
+        .events()
+        .iter()
+        .rev()
+        .take_while(|(_, event_type_byte, _, _, _)| *event_type_byte != RESET_EVENT)
+        .count();
+    let cost = replay_cost(context, num_events);
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(num_events as u64)],
+    ))
+}
+
+/// Return the BCS serialization of the object with the given ID
+pub fn object_bytes_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let bytes = serialize_object(object_id, obj)?;
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(bytes)]))
+}
+
+/// Return whether the objects with the given IDs have the same BCS serialization
+pub fn objects_equal_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    compare_objects(context, ty_args, args, false)
+}
+
+/// Return whether the objects with the given IDs have the same type and the same BCS serialization
+/// once the `VersionedID` they start with is left out, since it always differs between objects.
+/// The bytecode verifier requires the first field of an object to be its `VersionedID`.
+pub fn objects_equal_ignoring_id_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    compare_objects(context, ty_args, args, true)
+}
+
+/// Compare the BCS serializations of the objects with the given IDs, without their leading
+/// `VersionedID` and requiring the same type if `ignore_id` is set.
+/// Aborts with `EOBJECT_NOT_FOUND` if either object is not live.
+fn compare_objects(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+    ignore_id: bool,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id2: ObjectID = pop_arg!(args, AccountAddress).into();
+    let object_id1: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let (obj1, obj2) = match (inventory.get(&object_id1), inventory.get(&object_id2)) {
+        (Some(obj1), Some(obj2)) => (obj1, obj2),
+        _ => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let bytes1 = serialize_object(object_id1, obj1)?;
+    let bytes2 = serialize_object(object_id2, obj2)?;
+    let equal = if ignore_id {
+        obj1.type_ == obj2.type_
+            && bytes1.get(VERSIONED_ID_SIZE..) == bytes2.get(VERSIONED_ID_SIZE..)
+    } else {
+        bytes1 == bytes2
+    };
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(equal)]))
+}
+
+/// Return the bytes of the owning address, or of the ID of the parent object, of the live object
+/// with the given ID. Returns an empty vector for shared and immutable objects, and if there is
+/// no such object.
+pub fn get_owner_address(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let owner = match inventory.get(&object_id).map(|obj| obj.owner) {
+                Some(Owner::AddressOwner(owner) | Owner::ObjectOwner(owner)) => owner.to_vec(),
+                _ => vec![],
+            };
+            Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(owner)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the index of the first event transferring, freezing or sharing the object with the given
+/// ID, or `NOT_FOUND_EVENT_INDEX` if there is none
+pub fn object_created_at_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, context.events().len());
+    for (i, (_, event_type_byte, _, _, val)) in context.events().iter().enumerate() {
+        if is_transfer_event(*event_type_byte)
+            && get_object_id_from_event(*event_type_byte, val)? == Some(object_id)
+        {
+            return Ok(NativeResult::ok(cost, smallvec![Value::u64(i as u64)]));
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(NOT_FOUND_EVENT_INDEX)],
+    ))
+}
+
+/// Return the index of the last event about the object with the given ID, whether it transferred,
+/// froze, shared, updated, wrapped, unwrapped, dropped or deleted it, or `NOT_FOUND_EVENT_INDEX`
+/// if there is none
+pub fn object_last_touched_at_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let events = context.events();
+    for (i, (_, event_type_byte, _, _, val)) in events.iter().enumerate().rev() {
+        if get_object_id_from_event(*event_type_byte, val)? == Some(object_id) {
+            let cost = replay_cost(context, events.len() - i);
+            return Ok(NativeResult::ok(cost, smallvec![Value::u64(i as u64)]));
+        }
+    }
+    let cost = replay_cost(context, events.len());
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(NOT_FOUND_EVENT_INDEX)],
+    ))
+}
+
+/// Return the IDs of all the values of type `T` owned by any of the addresses whose bytes are in
+/// `owner_addresses`, replaying the event log once. Duplicate addresses are ignored.
+pub fn get_inventory_for_any(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner_addresses = pop_arg!(args, Vec<Value>);
+
+    let cost = replay_cost(context, tx_end_index);
+    let mut owners = BTreeSet::new();
+    for address in owner_addresses {
+        match SuiAddress::try_from(address.value_as::<Vec<u8>>()?.as_slice()) {
+            Ok(owner) => owners.insert(Owner::AddressOwner(owner)),
+            Err(_) => return Ok(NativeResult::err(cost, EINVALID_ADDRESS_BYTES)),
+        };
+    }
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let ids = inventory
+                .into_iter()
+                .filter(|(_, obj)| obj.type_ == ty_args[0] && owners.contains(&obj.owner))
+                .map(|(id, _)| id_value(id));
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(ids)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the number of transfers to an address or to an object of the object with the given ID
+/// in the window `[tx_begin_idx, tx_end_index)` of the event log
+pub fn transfer_count_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut count = 0;
+    for (_, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        if (*event_type_byte == EventType::TransferToAddress as u64
+            || *event_type_byte == EventType::TransferToObject as u64)
+            && get_object_id_from_event(*event_type_byte, val)? == Some(object_id)
+        {
+            count += 1;
+        }
+    }
+    Ok(NativeResult::ok(cost, smallvec![Value::u64(count)]))
+}
+
+/// Return a description of the structural problem of `event`, if any: an unknown event type, a
+/// value the ID of the object cannot be read from, or a recipient of the wrong length.
+/// These are the problems that make the replay of the event log fail with a `PartialVMError`.
+fn event_problem(event: &Event) -> Option<String> {
+    let (recipient, event_type_byte, _, _, val) = event;
+    if !SENTINEL_EVENTS.contains(event_type_byte) && parse_event_type(*event_type_byte).is_err() {
+        return Some(format!("unknown event type {}", event_type_byte));
+    }
+    if get_object_id_from_event(*event_type_byte, val).is_err() {
+        return Some(format!(
+            "the object ID cannot be read from the value of a {} event",
+            event_type_name(*event_type_byte)
+        ));
+    }
+    if parse_recipient(*event_type_byte, recipient).is_err() {
+        let expected = if *event_type_byte == EventType::TransferToObject as u64 {
+            "object ID"
+        } else {
+            "address"
+        };
+        return Some(format!(
+            "the recipient of a {} event is not a valid {} ({} bytes)",
+            event_type_name(*event_type_byte),
+            expected,
+            recipient.len()
+        ));
+    }
+    let value_is_valid = match *event_type_byte {
+        LOCKED_SHARED_OBJECT_EVENT => val
+            .copy_value()
+            .and_then(|v| v.value_as::<AccountAddress>())
+            .is_ok(),
+        STRICT_DELETE_CHECKING_EVENT => val.copy_value().and_then(|v| v.value_as::<bool>()).is_ok(),
+        RESTORED_SNAPSHOT_EVENT | EVENT_LOG_ID_EVENT => {
+            val.copy_value().and_then(|v| v.value_as::<u64>()).is_ok()
+        }
+        _ => true,
+    };
+    if !value_is_valid {
+        return Some(format!(
+            "the value of a {} event does not have the expected type",
+            event_type_name(*event_type_byte)
+        ));
+    }
+    None
+}
+
+/// Return the Move value of type `EventLogProblem` for each event of the log with a structural
+/// problem, in log order
+pub fn validate_event_log(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let cost = replay_cost(context, context.events().len());
+    let problems = context
+        .events()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, event)| {
+            event_problem(event).map(|description| {
+                Value::struct_(Struct::pack(vec![
+                    Value::u64(i as u64),
+                    Value::vector_u8(description.into_bytes()),
+                ]))
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(problems)],
+    ))
+}
+
+/// Return the `u64` field of `obj` at `field_path`, where each element of the path is the index of
+/// a field in the struct reached so far, or None if the path does not lead to a `u64`
+fn u64_field(obj: &OwnedObj, field_path: &[usize]) -> PartialVMResult<Option<u64>> {
+    Ok(get_nested_struct_field(obj.value.copy_value()?, field_path)
+        .and_then(|field| field.value_as::<u64>())
+        .ok())
+}
+
+/// Return the sum of the `u64` fields at `field_path` of the values of type `T` owned by
+/// `owner_address`, where each element of the path is the index of a field in the struct reached
+/// so far. Aborts with `EINVALID_FIELD_PATH` if the path does not lead to a `u64` in any value.
+pub fn sum_u64_field_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let field_path: Vec<usize> = pop_arg!(args, Vec<u64>)
+        .into_iter()
+        .map(|i| i as usize)
+        .collect();
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut sum: u64 = 0;
+    for obj in inventory.values() {
+        if obj.type_ != ty_args[0] || obj.owner != owner {
+            continue;
+        }
+        let field = match u64_field(obj, &field_path)? {
+            Some(field) => field,
+            None => return Ok(NativeResult::err(cost, EINVALID_FIELD_PATH)),
+        };
+        sum = sum.checked_add(field).ok_or_else(|| {
+            PartialVMError::new(StatusCode::ARITHMETIC_ERROR)
+                .with_message("sum of u64 fields overflows".to_string())
+        })?;
+    }
+    Ok(NativeResult::ok(cost, smallvec![Value::u64(sum)]))
+}
+
+/// Emit a `DeleteChildObject` event for every object owned by `owner_address` in the inventory
+/// built from the events before `tx_end_index`, so that these objects are gone from the inventory
+/// of subsequent transactions. `DeleteChildObject` is used because its value is the bare address of
+/// the deleted object, which is all that is known about an object not at hand.
+pub fn clear_owner_inventory_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    for (id, _) in inventory.iter().filter(|(_, obj)| obj.owner == owner) {
+        if !context.save_event(
+            vec![],
+            EventType::DeleteChildObject as u64,
+            Type::Address,
+            Value::address((*id).into()),
+        )? {
+            return Ok(NativeResult::err(cost, 0));
+        }
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Emit the marker recording the current state of the inventory as a snapshot
+pub fn snapshot_inventory_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    context.save_event(vec![], SNAPSHOT_EVENT, Type::Bool, Value::bool(true))?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Emit the marker restoring the state of the inventory recorded by the snapshot marker at index
+/// `snapshot_index` of the log. Aborts with `EEVENT_INDEX_OUT_OF_BOUNDS` if there is no snapshot
+/// marker at this index.
+pub fn restore_inventory_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let snapshot_index = pop_arg!(args, u64);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let is_snapshot = matches!(
+        context.events().get(snapshot_index as usize),
+        Some((_, SNAPSHOT_EVENT, _, _, _))
+    );
+    if !is_snapshot {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    context.save_event(
+        vec![],
+        RESTORED_SNAPSHOT_EVENT,
+        Type::U64,
+        Value::u64(snapshot_index),
+    )?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Emit the marker identifying the event log, so that the inventory replays of the log can be
+/// resumed from checkpoints
+pub fn mark_event_log_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    let log_id = NEXT_EVENT_LOG_ID.fetch_add(1, Ordering::Relaxed);
+    context.save_event(vec![], EVENT_LOG_ID_EVENT, Type::U64, Value::u64(log_id))?;
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return whether the live object with the given ID is shared. Returns false if there is no such
+/// object.
+pub fn is_shared_object_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let is_shared = inventory
+                .get(&object_id)
+                .map_or(false, |obj| obj.owner.is_shared());
+            Ok(NativeResult::ok(cost, smallvec![Value::bool(is_shared)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the BCS serialization of the `MoveTypeLayout` recorded for the object with the given ID,
+/// so that tools outside of Move can decode the bytes returned by `object_bytes_for_testing`
+pub fn object_layout_bcs(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    let bytes = bcs::to_bytes(&obj.layout).map_err(|e| {
+        PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR).with_message(format!(
+            "cannot serialize the layout of object {}: {}",
+            object_id, e
+        ))
+    })?;
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(bytes)]))
+}
+
+/// Abort with `EINVENTORY_NOT_EMPTY` if `owner_address` directly owns any object
+pub fn assert_inventory_empty_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) if owned_count(&inventory, owner) > 0 => {
+            Ok(NativeResult::err(cost, EINVENTORY_NOT_EMPTY))
+        }
+        Ok(_) => Ok(NativeResult::ok(cost, smallvec![])),
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the bytes of the IDs of the objects directly owned by `from` in the inventory built from
+/// the first `before` events of the log and by `to` in the one built from the first `after` events,
+/// in increasing order.
+pub fn ids_moved_between(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 4);
+
+    let after = pop_arg!(args, u64) as usize;
+    let before = pop_arg!(args, u64) as usize;
+    let to = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+    let from = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, after);
+    let events = context.events();
+    if before > after || after > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let before_inventory = match get_global_inventory(&events[..before])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let after_inventory = match get_global_inventory(&events[..after])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let moved: Vec<_> = before_inventory
+        .iter()
+        .filter(|(id, obj)| {
+            obj.owner == from && matches!(after_inventory.get(id), Some(cur) if cur.owner == to)
+        })
+        .map(|(id, _)| Value::vector_u8(id.to_vec()))
+        .collect();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(moved)],
+    ))
+}
+
+/// Return the number of shared objects in the inventory
+pub fn count_shared_objects(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let count = inventory
+                .values()
+                .filter(|obj| obj.owner.is_shared())
+                .count();
+            Ok(NativeResult::ok(cost, smallvec![Value::u64(count as u64)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the IDs of the objects in the inventory whose type is one of the type arguments, in
+/// increasing order, along with the index of the first type argument matching the type of each.
+/// The native accepts any number of type arguments; the Move declaration fixes how many.
+pub fn objects_of_any_type(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(!ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let (type_indices, ids): (Vec<_>, Vec<_>) = inventory
+        .iter()
+        .filter_map(|(id, obj)| {
+            let type_index = ty_args.iter().position(|ty| ty == &obj.type_)?;
+            Some((type_index as u64, id_value(*id)))
+        })
+        .unzip();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![
+            Value::vector_u64(type_indices),
+            Value::vector_for_testing_only(ids)
+        ],
+    ))
+}
+
+/// Replace the contents of the object of type `T` with the given ID by the value serialized in
+/// `object_bytes`, by emitting an update event for it: the object keeps its owner and gets the new
+/// contents from the next transaction on.
+pub fn replace_object_for_testing(
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_bytes = pop_arg!(args, Vec<u8>);
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+    let ty = ty_args.pop().unwrap();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let obj = match inventory.get(&object_id) {
+        Some(obj) => obj,
+        None => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    if obj.type_ != ty {
+        return Ok(NativeResult::err(cost, ETYPE_MISMATCH));
+    }
+    let new_value = match Value::simple_deserialize(&object_bytes, &obj.layout) {
+        Some(value) => value,
+        None => return Ok(NativeResult::err(cost, EINVALID_OBJECT_BYTES)),
+    };
+    let new_id = get_object_id(new_value.copy_value()?)?.value_as::<AccountAddress>()?;
+    if ObjectID::from(new_id) != object_id {
+        return Ok(NativeResult::err(cost, EINVALID_OBJECT_BYTES));
+    }
+    if !context.save_event(vec![], UPDATE_OBJECT_EVENT, ty, new_value)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the bytes of the IDs of all the objects that events in `[tx_begin_idx, tx_end_index)`
+/// are about, whatever the event, in increasing order
+pub fn all_seen_object_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut ids = BTreeSet::new();
+    for (_, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        ids.extend(get_object_id_from_event(*event_type_byte, val)?);
+    }
+    let ids = ids
+        .into_iter()
+        .map(|id: ObjectID| Value::vector_u8(id.to_vec()))
+        .collect();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return the number of events in `[tx_begin_idx, tx_end_index)` of each category, in the order:
+/// transfers to an address, transfers to an object, freezes, deletions, wraps and user events.
+/// Events of any other kind are not counted.
+pub fn event_histogram(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut counts = [0u64; 6];
+    for (_, event_type_byte, _, _, _) in &events[tx_begin_idx..tx_end_index] {
+        let category = if *event_type_byte == WRAPPED_OBJECT_EVENT {
+            4
+        } else {
+            match parse_event_type(*event_type_byte) {
+                Ok(EventType::TransferToAddress) => 0,
+                Ok(EventType::TransferToObject) => 1,
+                Ok(EventType::FreezeObject) => 2,
+                Ok(EventType::DeleteObjectID | EventType::DeleteChildObject) => 3,
+                Ok(EventType::User) => 5,
+                _ => continue,
+            }
+        };
+        counts[category] += 1;
+    }
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u64(counts)]))
+}
+
+/// Return whether `owner_address` directly owned the object with the given ID at some point of
+/// the window `[tx_begin_idx, tx_end_index)`: either at its start, or after one of its events
+pub fn ever_owned_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 4);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let inventory = match get_global_inventory(&events[..tx_begin_idx])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut ever_owned = matches!(inventory.get(&object_id), Some(obj) if obj.owner == owner);
+    for (recipient, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        if ever_owned {
+            break;
+        }
+        if get_object_id_from_event(*event_type_byte, val)? == Some(object_id) {
+            ever_owned = parse_recipient(*event_type_byte, recipient)? == Some(owner);
+        }
+    }
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(ever_owned)]))
+}
+
+/// Abort with `ELIVE_OBJECTS_CHANGED` unless the inventories built from the events before
+/// `tx_begin_idx` and before `tx_end_index` have the same objects, and with `EOBJECT_OWNER_CHANGED`
+/// unless every object has the same owner in both
+pub fn assert_no_ownership_changes(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let before = match get_global_inventory(&events[..tx_begin_idx])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let after = match get_global_inventory(&events[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    if !before.keys().eq(after.keys()) {
+        return Ok(NativeResult::err(cost, ELIVE_OBJECTS_CHANGED));
+    }
+    if before
+        .values()
+        .zip(after.values())
+        .any(|(old, new)| old.owner != new.owner)
+    {
+        return Ok(NativeResult::err(cost, EOBJECT_OWNER_CHANGED));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the IDs of the values of type `T` owned by `owner_address`, in decreasing order of their
+/// `u64` field at `field_path` and then in increasing order of ID. Aborts with
+/// `EINVALID_FIELD_PATH` if the path does not lead to a `u64` in any value.
+pub fn get_inventory_sorted_by_u64(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let field_path: Vec<usize> = pop_arg!(args, Vec<u64>)
+        .into_iter()
+        .map(|i| i as usize)
+        .collect();
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let mut keyed = vec![];
+    // `Inventory` is a `BTreeMap`, so this iterates in increasing order of ID
+    for (id, obj) in inventory {
+        if obj.type_ != ty_args[0] || obj.owner != owner {
+            continue;
+        }
+        match u64_field(&obj, &field_path)? {
+            Some(key) => keyed.push((key, id)),
+            None => return Ok(NativeResult::err(cost, EINVALID_FIELD_PATH)),
+        }
+    }
+    // stable, so objects with the same key stay in increasing order of ID
+    keyed.sort_by(|(key1, _), (key2, _)| key2.cmp(key1));
+    let ids = keyed.into_iter().map(|(_, id)| id_value(id));
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return the ID of the parent of the object with the given ID, as a vector with zero or one
+/// element: it is empty if the object is not owned by another object, or if there is no such object
+pub fn get_parent_object(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => {
+            let parent = inventory
+                .get(&object_id)
+                .and_then(|obj| parent_id_of(&obj.owner))
+                .map(id_value);
+            Ok(NativeResult::ok(
+                cost,
+                smallvec![Value::vector_for_testing_only(parent)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Emit a `User` event with the given tag and payload. The payload is the value of the event,
+/// and the BCS serialization of the tag is its recipient, which is empty for the events emitted
+/// with `event::emit`, so tagged events cannot be mistaken for them.
+pub fn emit_user_event_for_testing(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let payload = pop_arg!(args, Vec<u8>);
+    let tag = pop_arg!(args, u64);
+
+    // Gas amount doesn't matter as this is test only.
+    let cost = native_gas(context.cost_table(), NativeCostIndex::EMIT_EVENT, 0);
+    if !context.save_event(
+        tag.to_le_bytes().to_vec(),
+        EventType::User as u64,
+        Type::Vector(Box::new(Type::U8)),
+        Value::vector_u8(payload),
+    )? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the tag of `event` if it is a user event emitted by `emit_user_event_for_testing`
+fn user_event_tag(event: &Event) -> Option<u64> {
+    let (recipient, event_type_byte, _, _, _) = event;
+    if *event_type_byte != EventType::User as u64 {
+        return None;
+    }
+    let tag_bytes: [u8; 8] = recipient.as_slice().try_into().ok()?;
+    Some(u64::from_le_bytes(tag_bytes))
+}
+
+/// Return the payloads of the user events with the given tag in `[tx_begin_idx, tx_end_index)`,
+/// in log order
+pub fn user_events_with_tag(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let tag = pop_arg!(args, u64);
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut payloads = vec![];
+    for event in &events[tx_begin_idx..tx_end_index] {
+        if user_event_tag(event) == Some(tag) {
+            let (_, _, _, _, val) = event;
+            let payload = val.copy_value()?.value_as::<Vec<u8>>()?;
+            payloads.push(Value::vector_u8(payload));
+        }
+    }
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(payloads)],
+    ))
+}
+
+/// Abort with `EOBJECT_NOT_FOUND` unless the objects with the given IDs are both live, and with
+/// `ETYPE_MISMATCH` unless they have the same type
+pub fn assert_same_type_internal(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let object_id2: ObjectID = pop_arg!(args, AccountAddress).into();
+    let object_id1: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let (obj1, obj2) = match (inventory.get(&object_id1), inventory.get(&object_id2)) {
+        (Some(obj1), Some(obj2)) => (obj1, obj2),
+        _ => return Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    };
+    if obj1.type_ != obj2.type_ {
+        return Ok(NativeResult::err(cost, ETYPE_MISMATCH));
+    }
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+/// Return the number of objects in the inventory, whatever their owner
+pub fn count_live_objects(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 1);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index);
+    match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => Ok(NativeResult::ok(
+            cost,
+            smallvec![Value::u64(inventory.len() as u64)],
+        )),
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Return the bytes of the IDs of the objects wrapped by an event in
+/// `[tx_begin_idx, tx_end_index)` and not unwrapped, transferred or deleted by a later event of the
+/// window, in increasing order
+pub fn dangling_wrapped_ids(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 2);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+
+    let cost = replay_cost(context, tx_end_index.saturating_sub(tx_begin_idx));
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let mut wrapped = BTreeSet::new();
+    for (_, event_type_byte, _, _, val) in &events[tx_begin_idx..tx_end_index] {
+        let obj_id = match get_object_id_from_event(*event_type_byte, val)? {
+            Some(obj_id) => obj_id,
+            None => continue,
+        };
+        if *event_type_byte == WRAPPED_OBJECT_EVENT {
+            wrapped.insert(obj_id);
+        } else {
+            // a wrapped object is only referenced again once it is out of its wrapper
+            wrapped.remove(&obj_id);
+        }
+    }
+    let ids = wrapped
+        .into_iter()
+        .map(|id| Value::vector_u8(id.to_vec()))
+        .collect();
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_for_testing_only(ids)],
+    ))
+}
+
+/// Return the value of type `T` with the given ID, regardless of its owner, provided it has the
+/// version `expected_version`. Aborts with `EOBJECT_NOT_FOUND` if there is no object with this ID,
+/// `ETYPE_MISMATCH` if it is not of type `T` and `EVERSION_MISMATCH` if it has another version.
+pub fn take_object_by_id_at_version(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert_eq!(ty_args.len(), 1);
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let expected_version = pop_arg!(args, u64);
+    let object_id: ObjectID = pop_arg!(args, AccountAddress).into();
+
+    let cost = replay_cost(context, tx_end_index);
+    let mut inventory = match get_global_inventory(&context.events()[..tx_end_index])? {
+        Ok(inventory) => inventory,
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    match inventory.remove(&object_id) {
+        Some(obj) if obj.type_ != ty_args[0] => Ok(NativeResult::err(cost, ETYPE_MISMATCH)),
+        Some(obj) if obj.version.value() != expected_version => {
+            Ok(NativeResult::err(cost, EVERSION_MISMATCH))
+        }
+        Some(obj) => Ok(NativeResult::ok(cost, smallvec![obj.value])),
+        None => Ok(NativeResult::err(cost, EOBJECT_NOT_FOUND)),
+    }
+}
+
+/// Return how the number of objects directly owned by `owner_address` changed between the
+/// inventories built from the events before `tx_begin_idx` and before `tx_end_index`, as the
+/// magnitude of the change and whether it is a decrease
+pub fn owned_count_delta(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert_eq!(args.len(), 3);
+
+    let tx_end_index = pop_arg!(args, u64) as usize;
+    let tx_begin_idx = pop_arg!(args, u64) as usize;
+    let owner = Owner::AddressOwner(account_to_sui_address(pop_arg!(args, AccountAddress)));
+
+    let cost = replay_cost(context, tx_end_index);
+    let events = context.events();
+    if tx_begin_idx > tx_end_index || tx_end_index > events.len() {
+        return Ok(NativeResult::err(cost, EEVENT_INDEX_OUT_OF_BOUNDS));
+    }
+    let before = match get_global_inventory(&events[..tx_begin_idx])? {
+        Ok(inventory) => owned_count(&inventory, owner),
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let after = match get_global_inventory(&events[..tx_end_index])? {
+        Ok(inventory) => owned_count(&inventory, owner),
+        Err(abort_code) => return Ok(NativeResult::err(cost, abort_code)),
+    };
+    let is_negative = after < before;
+    let magnitude = if is_negative {
+        before - after
+    } else {
+        after - before
+    };
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(magnitude as u64), Value::bool(is_negative)],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versioned_id(id: Value) -> Value {
+        Value::struct_(Struct::pack(vec![
+            Value::struct_(Struct::pack(vec![id])),
+            Value::u64(1),
+        ]))
+    }
+
+    #[test]
+    fn deleted_id_bytes_of_versioned_id() {
+        let address = AccountAddress::new([7; AccountAddress::LENGTH]);
+        let id = Value::struct_(Struct::pack(vec![Value::address(address)]));
+        assert_eq!(get_deleted_id_bytes(&versioned_id(id)).unwrap(), address);
+    }
+
+    #[test]
+    fn deleted_id_bytes_of_malformed_versioned_id() {
+        // `ID` without its `bytes` field
+        let id = Value::struct_(Struct::pack(vec![]));
+        let err = get_deleted_id_bytes(&versioned_id(id)).unwrap_err();
+        assert_eq!(err.major_status(), StatusCode::DATA_FORMAT_ERROR);
+        // `ID` with a field of the wrong type
+        let id = Value::struct_(Struct::pack(vec![Value::u64(0)]));
+        assert!(get_deleted_id_bytes(&versioned_id(id)).is_err());
+        // not a struct at all
+        assert!(get_deleted_id_bytes(&Value::u64(0)).is_err());
+    }
+
+    #[test]
+    fn object_owner_round_trip() {
+        let parent_id = ObjectID::from(AccountAddress::new([3; AccountAddress::LENGTH]));
+        assert_eq!(parent_id_of(&object_owner(parent_id)), Some(parent_id));
+        assert_eq!(parent_id_of(&Owner::Shared), None);
+        assert_eq!(
+            parent_id_of(&Owner::AddressOwner(SuiAddress::from(parent_id))),
+            None
+        );
+    }
+
+    #[test]
+    fn transfer_to_object_recipient_is_parent_id() {
+        // the recipient of a `TransferToObject` event is the ID of the parent, as saved by
+        // `transfer_to_object`, and must resolve to that same ID when looking up children
+        let parent_id = ObjectID::from(AccountAddress::new([5; AccountAddress::LENGTH]));
+        let owner = get_new_owner(&EventType::TransferToObject, &parent_id.to_vec()).unwrap();
+        assert_eq!(owner, object_owner(parent_id));
+        assert_eq!(parent_id_of(&owner), Some(parent_id));
+    }
+
+    fn object_ids(n: u8) -> Vec<ObjectID> {
+        (1..=n)
+            .map(|i| ObjectID::from(AccountAddress::new([i; AccountAddress::LENGTH])))
+            .collect()
+    }
+
+    #[test]
+    fn object_balance_counts_created_and_deleted() {
+        let ids = object_ids(3);
+        let live = BTreeSet::from([ids[0]]);
+        let changes = [
+            // a new object, transferred twice
+            (EventType::TransferToAddress as u64, Some(ids[1])),
+            (EventType::ShareObject as u64, Some(ids[1])),
+            // a live object, deleted
+            (EventType::DeleteObjectID as u64, Some(ids[0])),
+            // an ID deleted without ever being transferred
+            (EventType::DeleteObjectID as u64, Some(ids[2])),
+            (EventType::User as u64, None),
+            (UPDATE_OBJECT_EVENT, Some(ids[1])),
+        ];
+        assert_eq!(
+            object_balance(live, &changes, &BTreeMap::new()),
+            ObjectBalance {
+                created: 1,
+                deleted: 1
+            }
+        );
+    }
+
+    #[test]
+    fn object_balance_of_wrapping() {
+        let ids = object_ids(2);
+        let live = BTreeSet::from([ids[0], ids[1]]);
+        let changes = [
+            (WRAPPED_OBJECT_EVENT, Some(ids[0])),
+            (UNWRAPPED_OBJECT_EVENT, Some(ids[0])),
+            (WRAPPED_OBJECT_EVENT, Some(ids[1])),
+        ];
+        assert_eq!(
+            object_balance(live, &changes, &BTreeMap::new()),
+            ObjectBalance {
+                created: 1,
+                deleted: 2
+            }
+        );
+    }
+
+    #[test]
+    fn object_balance_of_reset() {
+        let ids = object_ids(3);
+        let live = BTreeSet::from([ids[0], ids[1]]);
+        let changes = [
+            (RESET_EVENT, None),
+            // a previously live object re-created after the reset
+            (EventType::TransferToAddress as u64, Some(ids[0])),
+            (EventType::TransferToObject as u64, Some(ids[2])),
+        ];
+        assert_eq!(
+            object_balance(live, &changes, &BTreeMap::new()),
+            ObjectBalance {
+                created: 2,
+                deleted: 2
+            }
+        );
+    }
+
+    #[test]
+    fn object_balance_of_restore() {
+        let ids = object_ids(3);
+        let live = BTreeSet::from([ids[0], ids[1]]);
+        let changes = [
+            (SNAPSHOT_EVENT, None),
+            (EventType::DeleteObjectID as u64, Some(ids[0])),
+            (EventType::TransferToAddress as u64, Some(ids[2])),
+            // back to ids[0] and ids[1]
+            (RESTORED_SNAPSHOT_EVENT, None),
+        ];
+        let restored = BTreeMap::from([(3, BTreeSet::from([ids[0], ids[1]]))]);
+        assert_eq!(
+            object_balance(live, &changes, &restored),
+            ObjectBalance {
+                created: 2,
+                deleted: 2
+            }
+        );
+    }
+
+    #[test]
+    fn sentinel_events_do_not_collide_with_event_types() {
+        for sentinel in SENTINEL_EVENTS {
+            // event type bytes are parsed as `u8`, so larger sentinels would alias smaller bytes
+            assert!(sentinel <= u8::MAX as u64);
+            assert!(
+                EventType::try_from_primitive(sentinel as u8).is_err(),
+                "sentinel event {} is also an EventType",
+                sentinel
+            );
+        }
+        let distinct: BTreeSet<u64> = SENTINEL_EVENTS.into_iter().collect();
+        assert_eq!(distinct.len(), SENTINEL_EVENTS.len());
+    }
+
+    fn object(id: ObjectID, value: u64) -> Value {
+        let id = Value::struct_(Struct::pack(vec![Value::address(id.into())]));
+        Value::struct_(Struct::pack(vec![versioned_id(id), Value::u64(value)]))
+    }
+
+    fn event(recipient: Vec<u8>, event_type: EventType, obj: Value) -> Event {
+        (
+            recipient,
+            event_type as u64,
+            Type::U64,
+            MoveTypeLayout::U64,
+            obj,
+        )
+    }
+
+    #[test]
+    fn freeze_after_transfer_makes_object_immutable() {
+        let id = ObjectID::from(AccountAddress::new([9; AccountAddress::LENGTH]));
+        let owner = SuiAddress::from(ObjectID::from(AccountAddress::new(
+            [1; AccountAddress::LENGTH],
+        )));
+        let mut replay = InventoryReplay::default();
+        replay
+            .process_event(&event(
+                owner.to_vec(),
+                EventType::TransferToAddress,
+                object(id, 10),
+            ))
+            .unwrap();
+        assert_eq!(replay.inventory[&id].owner, Owner::AddressOwner(owner));
+        // the freeze event derives the ID of the object the same way as the transfer, so it
+        // overwrites the entry of the transfer: the object ends up immutable, with no signer left
+        replay
+            .process_event(&event(vec![], EventType::FreezeObject, object(id, 10)))
+            .unwrap();
+        assert_eq!(replay.inventory.len(), 1);
+        assert_eq!(replay.inventory[&id].owner, Owner::Immutable);
+        assert_eq!(replay.inventory[&id].signer, Some(Owner::Immutable));
+    }
+
+    #[test]
+    fn event_problems() {
+        let id = ObjectID::from(AccountAddress::new([4; AccountAddress::LENGTH]));
+        let owner = SuiAddress::from(id).to_vec();
+        let transfer = event(owner.clone(), EventType::TransferToAddress, object(id, 1));
+        assert_eq!(event_problem(&transfer), None);
+
+        let short_recipient = event(vec![1, 2], EventType::TransferToAddress, object(id, 1));
+        assert!(event_problem(&short_recipient)
+            .unwrap()
+            .contains("not a valid address"));
+
+        let no_id = event(owner.clone(), EventType::TransferToObject, Value::u64(1));
+        assert!(event_problem(&no_id).unwrap().contains("object ID"));
+
+        let mut unknown = event(owner, EventType::User, Value::u64(1));
+        unknown.1 = 100;
+        assert!(event_problem(&unknown)
+            .unwrap()
+            .contains("unknown event type"));
+
+        let reset = (
+            vec![],
+            RESET_EVENT,
+            Type::Bool,
+            MoveTypeLayout::Bool,
+            Value::bool(true),
+        );
+        assert_eq!(event_problem(&reset), None);
+        let bad_lock = (
+            vec![],
+            LOCKED_SHARED_OBJECT_EVENT,
+            Type::Bool,
+            MoveTypeLayout::Bool,
+            Value::bool(true),
+        );
+        assert!(event_problem(&bad_lock).is_some());
+    }
+
+    #[test]
+    fn restore_inventory_snapshot() {
+        let ids = object_ids(2);
+        let owner = SuiAddress::from(ids[0]).to_vec();
+        let marker =
+            |event_type_byte, type_, layout, val| (vec![], event_type_byte, type_, layout, val);
+        let events = vec![
+            event(
+                owner.clone(),
+                EventType::TransferToAddress,
+                object(ids[0], 1),
+            ),
+            marker(
+                SNAPSHOT_EVENT,
+                Type::Bool,
+                MoveTypeLayout::Bool,
+                Value::bool(true),
+            ),
+            event(
+                owner.clone(),
+                EventType::TransferToAddress,
+                object(ids[1], 2),
+            ),
+            event(
+                vec![],
+                EventType::DeleteChildObject,
+                Value::address(ids[0].into()),
+            ),
+            marker(
+                RESTORED_SNAPSHOT_EVENT,
+                Type::U64,
+                MoveTypeLayout::U64,
+                Value::u64(1),
+            ),
+        ];
+        let mut replay = InventoryReplay::default();
+        for event in &events {
+            replay.process_event(event).unwrap();
+        }
+        assert_eq!(replay.inventory.keys().collect::<Vec<_>>(), vec![&ids[0]]);
+        assert_eq!(replay.num_events, events.len());
+        assert!(replay.snapshots.contains_key(&1));
+
+        let mut replay = InventoryReplay::default();
+        let restore_unknown = marker(
+            RESTORED_SNAPSHOT_EVENT,
+            Type::U64,
+            MoveTypeLayout::U64,
+            Value::u64(0),
+        );
+        let err = replay.process_event(&restore_unknown).unwrap_err();
+        assert_eq!(err.major_status(), StatusCode::DATA_FORMAT_ERROR);
+    }
+
+    #[test]
+    fn replay_resumes_from_checkpoints_of_the_same_log() {
+        let ids = object_ids(2);
+        let owner = SuiAddress::from(ids[0]).to_vec();
+        let log_id = |id| {
+            (
+                vec![],
+                EVENT_LOG_ID_EVENT,
+                Type::U64,
+                MoveTypeLayout::U64,
+                Value::u64(id),
+            )
+        };
+        let live_ids = |events: &[Event]| {
+            replay_events(events)
+                .unwrap()
+                .inventory
+                .into_keys()
+                .collect::<Vec<_>>()
+        };
+        let transfer = |id| event(owner.clone(), EventType::TransferToAddress, object(id, 1));
+        let delete = |id: ObjectID| {
+            event(
+                vec![],
+                EventType::DeleteChildObject,
+                Value::address(id.into()),
+            )
+        };
+
+        let events = vec![log_id(u64::MAX), transfer(ids[0]), transfer(ids[1])];
+        assert_eq!(live_ids(&events), ids);
+        // a prefix of the log is not affected by the checkpoint of the whole log
+        assert_eq!(live_ids(&events[..2]), vec![ids[0]]);
+        assert_eq!(live_ids(&events), ids);
+
+        // the same log truncated and extended again
+        let events = vec![log_id(u64::MAX), transfer(ids[0]), delete(ids[0])];
+        assert!(live_ids(&events).is_empty());
+
+        // another log ending with the same event as a checkpoint of the first one
+        let events = vec![log_id(u64::MAX), transfer(ids[0]), transfer(ids[1])];
+        assert_eq!(live_ids(&events), ids);
+        let events = vec![log_id(u64::MAX - 1), transfer(ids[1]), transfer(ids[1])];
+        assert_eq!(live_ids(&events), vec![ids[1]]);
+    }
+
+    #[test]
+    fn parse_recipient_per_event_kind() {
+        let id = ObjectID::from(AccountAddress::new([6; AccountAddress::LENGTH]));
+        let address = SuiAddress::from(id);
+        let to_address = EventType::TransferToAddress as u64;
+        let to_object = EventType::TransferToObject as u64;
+        assert_eq!(
+            parse_recipient(to_address, &address.to_vec()).unwrap(),
+            Some(Owner::AddressOwner(address))
+        );
+        assert_eq!(
+            parse_recipient(UNWRAPPED_OBJECT_EVENT, &address.to_vec()).unwrap(),
+            Some(Owner::AddressOwner(address))
+        );
+        assert_eq!(
+            parse_recipient(to_object, &id.to_vec()).unwrap(),
+            Some(object_owner(id))
+        );
+        assert_eq!(
+            parse_recipient(EventType::FreezeObject as u64, &[]).unwrap(),
+            Some(Owner::Immutable)
+        );
+        // the recipient of events that do not give their object an owner is ignored
+        assert_eq!(
+            parse_recipient(WRAPPED_OBJECT_EVENT, &id.to_vec()).unwrap(),
+            None
+        );
+        assert_eq!(parse_recipient(EventType::User as u64, &[1]).unwrap(), None);
+
+        for event_type_byte in [to_address, to_object, UNWRAPPED_OBJECT_EVENT] {
+            let err = parse_recipient(event_type_byte, &[1, 2]).unwrap_err();
+            assert_eq!(err.major_status(), StatusCode::DATA_FORMAT_ERROR);
+            assert!(parse_recipient(event_type_byte, &[]).is_err());
+        }
+    }
+
+    #[test]
+    fn user_event_tags() {
+        let tagged = (
+            7u64.to_le_bytes().to_vec(),
+            EventType::User as u64,
+            Type::Vector(Box::new(Type::U8)),
+            MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            Value::vector_u8(vec![1, 2]),
+        );
+        assert_eq!(user_event_tag(&tagged), Some(7));
+        // events emitted with `event::emit` have no recipient
+        let untagged = event(vec![], EventType::User, Value::u64(1));
+        assert_eq!(user_event_tag(&untagged), None);
+        let id = ObjectID::from(AccountAddress::new([8; AccountAddress::LENGTH]));
+        let transfer = event(
+            SuiAddress::from(id).to_vec(),
+            EventType::TransferToAddress,
+            object(id, 1),
+        );
+        assert_eq!(user_event_tag(&transfer), None);
+    }
+}
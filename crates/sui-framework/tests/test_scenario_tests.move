@@ -3,10 +3,15 @@
 
 #[test_only]
 module sui::test_scenarioTests {
+    use sui::event;
     use sui::id;
     use sui::test_scenario::{Self, Scenario};
     use sui::transfer::{Self, ChildRef};
     use sui::tx_context;
+    use sui::utf8;
+    use std::bcs;
+    use std::option;
+    use std::vector;
 
     const ID_BYTES_MISMATCH: u64 = 0;
     const VALUE_MISMATCH: u64 = 1;
@@ -33,6 +38,11 @@ module sui::test_scenarioTests {
         child2: ChildRef<Object>,
     }
 
+    /// User events must be structs
+    struct TestEvent has copy, drop {
+        value: u64,
+    }
+
     #[test]
     fun test_wrap_unwrap() {
         let sender = @0x0;
@@ -354,6 +364,2482 @@ module sui::test_scenarioTests {
             };
     }
 
+    #[test]
+    fun test_object_exists() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            let obj = Object { id: versioned_id, value: 10 };
+            transfer::transfer(obj, copy sender);
+            // the object is not visible until the transaction ends
+            assert!(!test_scenario::object_exists(&scenario, obj_id), 0);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(test_scenario::object_exists(&scenario, obj_id), 1);
+            let Object { id: versioned_id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(versioned_id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(!test_scenario::object_exists(&scenario, obj_id), 2);
+        }
+    }
+
+    #[test]
+    fun test_ids_by_owner_kind() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let owned_versioned_id = test_scenario::new_id(&mut scenario);
+        let shared_versioned_id = test_scenario::new_id(&mut scenario);
+        let owned_id = *id::inner(&owned_versioned_id);
+        let shared_id = *id::inner(&shared_versioned_id);
+        {
+            transfer::transfer(Object { id: owned_versioned_id, value: 10 }, copy sender);
+            transfer::share_object(Object { id: shared_versioned_id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(test_scenario::ids_by_owner_kind<Object>(&scenario, sender, 0) == vector[owned_id], 0);
+            assert!(test_scenario::ids_by_owner_kind<Object>(&scenario, sender, 3) == vector[shared_id], 1);
+            assert!(vector::is_empty(&test_scenario::ids_by_owner_kind<Object>(&scenario, sender, 2)), 2);
+        }
+    }
+
+    #[test]
+    fun test_num_owned() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id1 = test_scenario::new_id(&mut scenario);
+            let id2 = test_scenario::new_id(&mut scenario);
+            let id3 = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id: id1, value: 10 }, copy sender);
+            transfer::transfer(Object { id: id2, value: 20 }, copy sender);
+            transfer::transfer(Object { id: id3, value: 30 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(test_scenario::num_owned<Object>(&scenario, sender) == 2, 0);
+            assert!(test_scenario::num_owned<Object>(&scenario, other) == 1, 1);
+            assert!(test_scenario::num_owned<Wrapper>(&scenario, sender) == 0, 2);
+        }
+    }
+
+    #[test]
+    fun test_take_owned_transitive() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        create_parent_and_object(&mut scenario);
+
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            // the object is owned by the parent, which is owned by the sender
+            assert!(!test_scenario::can_take_owned<Object>(&scenario), 0);
+            let child = test_scenario::take_owned_transitive<Object>(&mut scenario);
+            assert!(child.value == 10, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, child);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 3 /* EMPTY_INVENTORY */)]
+    fun test_take_owned_transitive_other_sender() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        create_parent_and_object(&mut scenario);
+
+        let other = @0x1;
+        test_scenario::next_tx(&mut scenario, &other);
+        let child = test_scenario::take_owned_transitive<Object>(&mut scenario);
+        test_scenario::return_owned(&mut scenario, child);
+    }
+
+    #[test]
+    fun test_most_recent_id_for_owner() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        assert!(option::is_none(&test_scenario::most_recent_id_for_owner<Object>(&scenario, sender)), 0);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let id2 = *id::inner(&versioned_id2);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 10 }, copy sender);
+            transfer::transfer(Object { id: versioned_id2, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let most_recent = test_scenario::most_recent_id_for_owner<Object>(&scenario, sender);
+            assert!(option::contains(&most_recent, &id2), 1);
+        }
+    }
+
+    #[test]
+    fun test_object_version() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            // freshly created objects start at the initial version
+            assert!(test_scenario::object_version(&scenario, obj_id) == 0, 0);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 102 /* EOBJECT_NOT_FOUND */)]
+    fun test_object_version_not_found() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        id::delete(versioned_id);
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::object_version(&scenario, obj_id);
+    }
+
+    #[test]
+    fun test_frozen_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let frozen_versioned_id = test_scenario::new_id(&mut scenario);
+        let owned_versioned_id = test_scenario::new_id(&mut scenario);
+        let frozen_id = *id::inner(&frozen_versioned_id);
+        {
+            transfer::freeze_object(Object { id: frozen_versioned_id, value: 10 });
+            transfer::transfer(Object { id: owned_versioned_id, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(test_scenario::frozen_ids(&scenario) == vector[frozen_id], 0);
+        }
+    }
+
+    #[test]
+    fun test_shared_and_frozen_are_distinct() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let shared_id = test_scenario::new_id(&mut scenario);
+            let frozen_id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id: shared_id, value: 10 });
+            transfer::freeze_object(Object { id: frozen_id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            // each kind of unowned object can only be taken through its own API
+            let shared = test_scenario::take_shared<Object>(&mut scenario);
+            let frozen = test_scenario::take_immutable<Object>(&mut scenario);
+            assert!(test_scenario::borrow_mut(&mut shared).value == 10, VALUE_MISMATCH);
+            assert!(test_scenario::borrow(&frozen).value == 20, VALUE_MISMATCH);
+            test_scenario::return_shared(&mut scenario, shared);
+            test_scenario::return_immutable(&mut scenario, frozen);
+        }
+    }
+
+    #[test]
+    fun test_take_by_id() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            // the sender can take the object even though `other` owns it
+            let obj = test_scenario::take_by_id<Object>(&mut scenario, obj_id);
+            assert!(obj.value == 10, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+        test_scenario::next_tx(&mut scenario, &other);
+        {
+            assert!(test_scenario::can_take_owned<Object>(&scenario), 0);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 103 /* ETYPE_MISMATCH */)]
+    fun test_take_by_id_type_mismatch() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let wrapper = test_scenario::take_by_id<Wrapper>(&mut scenario, obj_id);
+        test_scenario::return_owned(&mut scenario, wrapper);
+    }
+
+    #[test]
+    fun test_return_unwrapped() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 10 };
+            transfer::transfer(Wrapper { id, child }, copy sender);
+        };
+        // the wrapped object is not in the inventory
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(!test_scenario::can_take_owned<Object>(&scenario), 0);
+            let Wrapper { id, child } = test_scenario::take_owned<Wrapper>(&mut scenario);
+            id::delete(id);
+            test_scenario::return_unwrapped(child, sender);
+        };
+        // once unwrapped, it is back in the inventory of the sender
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(test_scenario::can_take_owned<Object>(&scenario), 1);
+            assert!(!test_scenario::can_take_owned<Wrapper>(&scenario), 2);
+        }
+    }
+
+    #[test]
+    fun test_ids_with_owners() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let parent_versioned_id = test_scenario::new_id(&mut scenario);
+        let parent_id = *id::inner(&parent_versioned_id);
+        let child = Object { id: test_scenario::new_id(&mut scenario), value: 10 };
+        let child_id = *id::id(&child);
+        let sibling_versioned_id = test_scenario::new_id(&mut scenario);
+        let sibling_id = *id::inner(&sibling_versioned_id);
+        {
+            let (parent_versioned_id, child_ref) = transfer::transfer_to_object_id(child, parent_versioned_id);
+            transfer::transfer(Parent { id: parent_versioned_id, child: child_ref }, copy sender);
+            transfer::transfer(Object { id: sibling_versioned_id, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let (ids, owners) = test_scenario::ids_with_owners<Object>(&scenario, sender);
+            assert!(vector::length(&ids) == 2, 0);
+            let (_, child_idx) = vector::index_of(&ids, &child_id);
+            let (_, sibling_idx) = vector::index_of(&ids, &sibling_id);
+            let child_owner = vector::borrow(&owners, child_idx);
+            let sibling_owner = vector::borrow(&owners, sibling_idx);
+            assert!(test_scenario::owner_info_kind(child_owner) == 1, 1);
+            assert!(id::new(test_scenario::owner_info_address(child_owner)) == parent_id, 2);
+            assert!(test_scenario::owner_info_kind(sibling_owner) == 0, 3);
+            assert!(test_scenario::owner_info_address(sibling_owner) == sender, 4);
+        }
+    }
+
+    #[test]
+    fun test_assert_owned_by() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_owned_by(&scenario, obj_id, sender);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 105 /* EWRONG_OWNER */)]
+    fun test_assert_owned_by_wrong_owner() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_owned_by(&scenario, obj_id, @0x1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 104 /* ENOT_ADDRESS_OWNED */)]
+    fun test_assert_owned_by_shared() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_owned_by(&scenario, obj_id, sender);
+    }
+
+    #[test]
+    fun test_delete_object_for_testing() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            test_scenario::delete_object_for_testing(obj);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            assert!(!test_scenario::object_exists(&scenario, obj_id), 0);
+            assert!(!test_scenario::can_take_owned<Object>(&scenario), 1);
+        }
+    }
+
+    #[test]
+    fun test_last_tx_index() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin_idx = test_scenario::last_tx_index();
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        assert!(test_scenario::last_tx_index() == begin_idx + 1, 0);
+        test_scenario::print_transfer_log(begin_idx);
+    }
+
+    #[test]
+    fun test_transferred_object_ids_to() {
+        let sender = @0x0;
+        let recipient = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let begin_idx = test_scenario::last_tx_index();
+        let id1 = test_scenario::new_id(&mut scenario);
+        let id2 = test_scenario::new_id(&mut scenario);
+        let id3 = test_scenario::new_id(&mut scenario);
+        let obj_id1 = *id::inner(&id1);
+        let obj_id2 = *id::inner(&id2);
+        {
+            transfer::transfer(Object { id: id1, value: 1 }, copy recipient);
+            transfer::transfer(Object { id: id2, value: 2 }, copy recipient);
+            transfer::transfer(Object { id: id3, value: 3 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &recipient);
+        {
+            // delete the object with value 2; only the first object should remain
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id2);
+            test_scenario::delete_object_for_testing(obj);
+        };
+        test_scenario::next_tx(&mut scenario, &recipient);
+        let ids = test_scenario::transferred_object_ids_to(recipient, begin_idx);
+        assert!(ids == vector::singleton(obj_id1), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    fun test_retransfer_round_trip() {
+        let addr_a = @0x0;
+        let addr_b = @0x1;
+        let scenario = test_scenario::begin(&addr_a);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy addr_b);
+        };
+        test_scenario::next_tx(&mut scenario, &addr_b);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            transfer::transfer(obj, copy addr_a);
+        };
+        test_scenario::next_tx(&mut scenario, &addr_b);
+        {
+            // the object has left B, so B's inventory is empty again
+            assert!(!test_scenario::can_take_owned<Object>(&scenario), 0);
+            assert!(test_scenario::num_owned<Object>(&scenario, addr_b) == 0, 1);
+            assert!(test_scenario::num_owned<Object>(&scenario, addr_a) == 1, 2);
+            test_scenario::assert_owned_by(&scenario, obj_id, addr_a);
+        };
+        test_scenario::next_tx(&mut scenario, &addr_a);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            assert!(*id::id(&obj) == obj_id, ID_BYTES_MISMATCH);
+            test_scenario::return_owned(&mut scenario, obj);
+        }
+    }
+
+    #[test]
+    fun test_retransfer_parent_moves_children() {
+        let addr_a = @0x0;
+        let addr_b = @0x1;
+        let scenario = test_scenario::begin(&addr_a);
+        create_parent_and_object(&mut scenario);
+
+        test_scenario::next_tx(&mut scenario, &addr_a);
+        {
+            let parent = test_scenario::take_owned<Parent>(&mut scenario);
+            transfer::transfer(parent, copy addr_b);
+        };
+        test_scenario::next_tx(&mut scenario, &addr_b);
+        {
+            // the child follows its parent to the new owner
+            let parent = test_scenario::take_owned<Parent>(&mut scenario);
+            let child = test_scenario::take_child_object<Parent, Object>(&mut scenario, &parent);
+            test_scenario::return_owned(&mut scenario, parent);
+            test_scenario::return_owned(&mut scenario, child);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 3 /* EMPTY_INVENTORY */)]
+    fun test_retransfer_parent_previous_owner_loses_children() {
+        let addr_a = @0x0;
+        let addr_b = @0x1;
+        let scenario = test_scenario::begin(&addr_a);
+        create_parent_and_object(&mut scenario);
+
+        test_scenario::next_tx(&mut scenario, &addr_a);
+        let parent = test_scenario::take_owned<Parent>(&mut scenario);
+        transfer::transfer(parent, copy addr_b);
+        test_scenario::next_tx(&mut scenario, &addr_b);
+        let parent = test_scenario::take_owned<Parent>(&mut scenario);
+        test_scenario::next_tx(&mut scenario, &addr_a);
+        // A no longer signs for the child
+        let child = test_scenario::take_child_object<Parent, Object>(&mut scenario, &parent);
+        test_scenario::return_owned(&mut scenario, child);
+        test_scenario::return_owned(&mut scenario, parent);
+    }
+
+    #[test]
+    fun test_reset() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::can_take_owned<Object>(&scenario), 0);
+
+        let begin_idx = test_scenario::last_tx_index();
+        test_scenario::reset(&mut scenario);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), 1);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            assert!(obj.value == 20, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+        // indices refer to the raw log: the reset marker sits at `begin_idx`
+        assert!(vector::length(&test_scenario::transferred_object_ids_to(sender, begin_idx)) == 1, 2);
+    }
+
+    #[test]
+    fun test_typed_deleted_object_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin_idx = test_scenario::last_tx_index();
+        let id1 = test_scenario::new_id(&mut scenario);
+        let obj_id1 = *id::inner(&id1);
+        {
+            transfer::transfer(Object { id: id1, value: 10 }, copy sender);
+            create_parent_and_object(&mut scenario);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let Object { id, value: _ } = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id1);
+            id::delete(id);
+            let parent = test_scenario::take_owned<Parent>(&mut scenario);
+            test_scenario::delete_object_for_testing(parent);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let deleted = test_scenario::typed_deleted_object_ids<Object>(begin_idx);
+        assert!(deleted == vector::singleton(obj_id1), ID_BYTES_MISMATCH);
+        assert!(vector::length(&test_scenario::typed_deleted_object_ids<Parent>(begin_idx)) == 1, 0);
+        // deletions before the index are not reported
+        assert!(vector::is_empty(&test_scenario::typed_deleted_object_ids<Object>(test_scenario::last_tx_index())), 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 109 /* EEVENT_INDEX_OUT_OF_BOUNDS */)]
+    fun test_typed_deleted_object_ids_out_of_bounds() {
+        test_scenario::typed_deleted_object_ids<Object>(test_scenario::last_tx_index() + 1);
+    }
+
+    #[test]
+    fun test_take_shared_by_id() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 10 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+        };
+        let other = @0x1;
+        test_scenario::next_tx(&mut scenario, &other);
+        {
+            let wrapper = test_scenario::take_shared_by_id<Object>(&mut scenario, obj_id);
+            let obj = test_scenario::borrow_mut(&mut wrapper);
+            assert!(obj.value == 10, VALUE_MISMATCH);
+            obj.value = 11;
+            test_scenario::return_shared(&mut scenario, wrapper);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 7 /* ENotSharedObject */)]
+    fun test_take_shared_by_id_frozen() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::freeze_object(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let wrapper = test_scenario::take_shared_by_id<Object>(&mut scenario, obj_id);
+        test_scenario::return_shared(&mut scenario, wrapper);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 106 /* ENOT_SHARED */)]
+    fun test_take_shared_by_id_owned() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let wrapper = test_scenario::take_shared_by_id<Object>(&mut scenario, obj_id);
+        test_scenario::return_shared(&mut scenario, wrapper);
+    }
+
+    #[test]
+    fun test_num_events_of_type() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+            event::emit(TestEvent { value: 1 });
+            event::emit(TestEvent { value: 0 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child: obj }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_events_of_type(6 /* User */) == 2, 0);
+        assert!(test_scenario::num_events_of_type(0 /* TransferToAddress */) == 2, 1);
+        assert!(test_scenario::num_events_of_type(255 /* wrapped */) == 1, 2);
+        assert!(test_scenario::num_events_of_type(2 /* FreezeObject */) == 0, 3);
+    }
+
+    #[test]
+    fun test_most_recent_receiver() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin_idx = test_scenario::last_tx_index();
+        assert!(vector::is_empty(&test_scenario::most_recent_receiver(begin_idx)), 0);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, @0x2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 30 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let expected = x"0000000000000000000000000000000000000002";
+        assert!(test_scenario::most_recent_receiver(begin_idx) == expected, 1);
+        assert!(vector::is_empty(&test_scenario::most_recent_receiver(test_scenario::last_tx_index())), 2);
+    }
+
+    #[test]
+    fun test_owned_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let owned_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 30 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let ids = test_scenario::owned_ids<Object>(&scenario, sender, false);
+        assert!(ids == vector::singleton(owned_id), ID_BYTES_MISMATCH);
+        let ids = test_scenario::owned_ids<Object>(&scenario, sender, true);
+        assert!(vector::length(&ids) == 2, 0);
+        assert!(vector::contains(&ids, &owned_id), 1);
+    }
+
+    #[test]
+    fun test_event_sender() {
+        let alice = @0xA;
+        let bob = @0xB;
+        let scenario = test_scenario::begin(&alice);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy bob);
+        };
+        test_scenario::next_tx(&mut scenario, &bob);
+        let bob_idx = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            transfer::transfer(obj, copy alice);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        assert!(test_scenario::event_sender(&scenario, 0) == alice, 0);
+        assert!(test_scenario::event_sender(&scenario, bob_idx) == bob, 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 8 /* EInvalidEventIndex */)]
+    fun test_event_sender_not_emitted() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        test_scenario::event_sender(&scenario, test_scenario::last_tx_index());
+    }
+
+    #[test]
+    fun test_has_received_object_of_type() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::has_received_object_of_type<Object>(&scenario, other), 0);
+        assert!(!test_scenario::has_received_object_of_type<Object>(&scenario, sender), 1);
+        assert!(!test_scenario::has_received_object_of_type<Parent>(&scenario, other), 2);
+    }
+
+    #[test]
+    fun test_object_size() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        let wrapper_versioned_id = test_scenario::new_id(&mut scenario);
+        let wrapper_id = *id::inner(&wrapper_versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 20 };
+            transfer::transfer(Wrapper { id: wrapper_versioned_id, child }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        // VersionedID (20-byte ID + u64 version) followed by a u64
+        assert!(test_scenario::object_size(&scenario, obj_id) == 36, 0);
+        // a wrapper is its own VersionedID followed by the wrapped object
+        assert!(test_scenario::object_size(&scenario, wrapper_id) == 64, 1);
+    }
+
+    #[test]
+    fun test_object_size_after_update() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::freeze_object(Object { id: versioned_id, value: 10 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::object_size(&scenario, obj_id) == 36, 0);
+        {
+            let wrapper = test_scenario::take_shared<Object>(&mut scenario);
+            let shared_id = *id::id(test_scenario::borrow_mut(&mut wrapper));
+            test_scenario::borrow_mut(&mut wrapper).value = 21;
+            test_scenario::return_shared(&mut scenario, wrapper);
+            test_scenario::next_tx(&mut scenario, &sender);
+            // the layout recorded by the update event is still usable
+            assert!(test_scenario::object_size(&scenario, shared_id) == 36, 1);
+        }
+    }
+
+    #[test]
+    fun test_debug_object_json() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let json = test_scenario::debug_object_json(&scenario, obj_id);
+        assert!(!vector::is_empty(utf8::bytes(&json)), 0);
+    }
+
+    #[test]
+    fun test_wrapped_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let deleted_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(vector::is_empty(&test_scenario::wrapped_ids(&scenario)), 0);
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child: obj }, copy sender);
+            // deleted objects are not reported as wrapped
+            let Object { id, value: _ } = test_scenario::take_owned_by_id<Object>(&mut scenario, deleted_id);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::wrapped_ids(&scenario) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+        {
+            let Wrapper { id, child } = test_scenario::take_owned<Wrapper>(&mut scenario);
+            id::delete(id);
+            test_scenario::return_unwrapped(child, sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(vector::is_empty(&test_scenario::wrapped_ids(&scenario)), 1);
+    }
+
+    #[test]
+    fun test_transfer_to_object_for_testing() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let parent_versioned_id = test_scenario::new_id(&mut scenario);
+        let parent_id = *id::inner(&parent_versioned_id);
+        let child_versioned_id = test_scenario::new_id(&mut scenario);
+        let child_id = *id::inner(&child_versioned_id);
+        {
+            let child = Object { id: child_versioned_id, value: 20 };
+            test_scenario::transfer_to_object_for_testing(child, parent_id);
+            transfer::transfer(Object { id: parent_versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let parent = test_scenario::take_owned<Object>(&mut scenario);
+            assert!(*id::id(&parent) == parent_id, ID_BYTES_MISMATCH);
+            let child = test_scenario::take_child_object<Object, Object>(&mut scenario, &parent);
+            assert!(*id::id(&child) == child_id, ID_BYTES_MISMATCH);
+            test_scenario::return_owned(&mut scenario, parent);
+            test_scenario::return_owned(&mut scenario, child);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 107 /* EINVALID_PARENT */)]
+    fun test_transfer_to_object_for_testing_self() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        let obj = Object { id: versioned_id, value: 10 };
+        test_scenario::transfer_to_object_for_testing(obj, obj_id);
+    }
+
+    #[test]
+    fun test_inventory_digest() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let empty_digest = test_scenario::inventory_digest(&scenario);
+        assert!(vector::length(&empty_digest) == 32, 0);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let digest = test_scenario::inventory_digest(&scenario);
+        assert!(digest != empty_digest, 1);
+        {
+            // taking and returning an object without touching it is a no-op
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::inventory_digest(&scenario) == digest, 2);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            obj.value = 11;
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::inventory_digest(&scenario) != digest, 3);
+    }
+
+    #[test]
+    fun test_take_immutable_by_id() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::freeze_object(Object { id: versioned_id, value: 10 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let wrapper = test_scenario::take_immutable_by_id<Object>(&mut scenario, obj_id);
+            assert!(test_scenario::borrow(&wrapper).value == 10, VALUE_MISMATCH);
+            test_scenario::return_immutable(&mut scenario, wrapper);
+        }
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 108 /* ENOT_IMMUTABLE */)]
+    fun test_take_immutable_by_id_shared() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let wrapper = test_scenario::take_immutable_by_id<Object>(&mut scenario, obj_id);
+        test_scenario::return_immutable(&mut scenario, wrapper);
+    }
+
+    #[test]
+    fun test_inventory_ids_at() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let after_create = test_scenario::last_tx_index();
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let after_delete = test_scenario::last_tx_index();
+        assert!(vector::is_empty(&test_scenario::inventory_ids_at(0)), 0);
+        assert!(test_scenario::inventory_ids_at(after_create) == vector::singleton(obj_id), 1);
+        assert!(vector::is_empty(&test_scenario::inventory_ids_at(after_delete)), 2);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 109 /* EEVENT_INDEX_OUT_OF_BOUNDS */)]
+    fun test_inventory_ids_at_out_of_bounds() {
+        test_scenario::inventory_ids_at(test_scenario::last_tx_index() + 1);
+    }
+
+    #[test]
+    fun test_num_objects_owned() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+            // the parent is counted, but not its child
+            create_parent_and_object(&mut scenario);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_objects_owned(&scenario, sender) == 2, 0);
+        assert!(test_scenario::num_objects_owned(&scenario, other) == 1, 1);
+        assert!(test_scenario::num_objects_owned(&scenario, @0x2) == 0, 2);
+    }
+
+    #[test]
+    fun test_distinct_owners() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, @0x2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 30 }, @0x2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 40 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let owners = test_scenario::distinct_owners(&scenario);
+        assert!(vector::length(&owners) == 2, 0);
+        assert!(*vector::borrow(&owners, 0) == x"0000000000000000000000000000000000000001", 1);
+        assert!(*vector::borrow(&owners, 1) == x"0000000000000000000000000000000000000002", 2);
+    }
+
+    #[test]
+    fun test_change_owner_for_testing() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::change_owner_for_testing(obj_id, 0 /* address */, other);
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_owned_by(&scenario, obj_id, other);
+
+        test_scenario::change_owner_for_testing(obj_id, 2 /* immutable */, @0x0);
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::frozen_ids(&scenario) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 101 /* EINVALID_OWNER_KIND */)]
+    fun test_change_owner_for_testing_invalid_kind() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::change_owner_for_testing(obj_id, 4, sender);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 107 /* EINVALID_PARENT */)]
+    fun test_change_owner_for_testing_self() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::change_owner_for_testing(obj_id, 1 /* object */, id::id_address(&obj_id));
+    }
+
+    #[test]
+    fun test_assert_deleted() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin_idx = test_scenario::last_tx_index();
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_deleted(obj_id, begin_idx);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 110 /* EOBJECT_NOT_DELETED */)]
+    fun test_assert_deleted_wrapped() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin_idx = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child: obj }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_deleted(obj_id, begin_idx);
+    }
+
+    #[test]
+    fun test_assert_wrapped() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin_idx = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child: obj }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_wrapped(obj_id, begin_idx);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 111 /* EOBJECT_NOT_WRAPPED */)]
+    fun test_assert_wrapped_deleted() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin_idx = test_scenario::last_tx_index();
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_wrapped(obj_id, begin_idx);
+    }
+
+    #[test]
+    fun test_type_of_object() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let id1 = test_scenario::new_id(&mut scenario);
+        let obj_id1 = *id::inner(&id1);
+        let id2 = test_scenario::new_id(&mut scenario);
+        let obj_id2 = *id::inner(&id2);
+        let id3 = test_scenario::new_id(&mut scenario);
+        let wrapper_id = *id::inner(&id3);
+        {
+            transfer::transfer(Object { id: id1, value: 10 }, copy sender);
+            transfer::transfer(Object { id: id2, value: 20 }, copy sender);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 30 };
+            transfer::transfer(Wrapper { id: id3, child }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let type1 = test_scenario::type_of_object(&scenario, obj_id1);
+        assert!(!vector::is_empty(utf8::bytes(&type1)), 0);
+        assert!(type1 == test_scenario::type_of_object(&scenario, obj_id2), 1);
+        assert!(type1 != test_scenario::type_of_object(&scenario, wrapper_id), 2);
+    }
+
+    #[test]
+    fun test_owned_ids_multiple_types() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+            create_parent_and_object(&mut scenario);
+            create_parent_and_object(&mut scenario);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let (objects, parents) = test_scenario::owned_ids_2<Object, Parent>(&scenario, sender);
+        assert!(objects == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+        assert!(vector::length(&parents) == 2, 0);
+        let (objects, parents, wrappers) = test_scenario::owned_ids_3<Object, Parent, Wrapper>(&scenario, sender);
+        assert!(vector::length(&objects) == 1, 1);
+        assert!(vector::length(&parents) == 2, 2);
+        assert!(vector::is_empty(&wrappers), 3);
+    }
+
+    #[test]
+    fun test_detect_id_conflicts() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin_idx = test_scenario::last_tx_index();
+        assert!(vector::is_empty(&test_scenario::detect_id_conflicts(0, begin_idx)), 0);
+        {
+            test_scenario::change_owner_for_testing(obj_id, 0 /* address */, @0x1);
+            test_scenario::change_owner_for_testing(obj_id, 0 /* address */, @0x2);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let conflicts = test_scenario::detect_id_conflicts(begin_idx, test_scenario::last_tx_index());
+        assert!(conflicts == vector::singleton(id::bytes(&obj_id)), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    fun test_children_of() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let parent_versioned_id = test_scenario::new_id(&mut scenario);
+        let parent_id = *id::inner(&parent_versioned_id);
+        let child_versioned_id = test_scenario::new_id(&mut scenario);
+        let child_id = *id::inner(&child_versioned_id);
+        {
+            let child = Object { id: child_versioned_id, value: 10 };
+            let grandchild = Object { id: test_scenario::new_id(&mut scenario), value: 20 };
+            transfer::transfer(Object { id: parent_versioned_id, value: 30 }, copy sender);
+            test_scenario::transfer_to_object_for_testing(child, parent_id);
+            test_scenario::transfer_to_object_for_testing(grandchild, child_id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let children = test_scenario::children_of(&scenario, parent_id);
+        assert!(children == vector::singleton(id::bytes(&child_id)), ID_BYTES_MISMATCH);
+        assert!(vector::length(&test_scenario::children_of(&scenario, child_id)) == 1, 0);
+    }
+
+    #[test]
+    fun test_take_one() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_one<Object>(&mut scenario);
+            assert!(obj.value == 10, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 112 /* ENO_MATCHING_OBJECT */)]
+    fun test_take_one_empty() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        test_scenario::next_tx(&mut scenario, &sender);
+        let obj = test_scenario::take_one<Object>(&mut scenario);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 113 /* EMULTIPLE_MATCHING_OBJECTS */)]
+    fun test_take_one_ambiguous() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id1 = test_scenario::new_id(&mut scenario);
+            let id2 = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id: id1, value: 10 }, copy sender);
+            transfer::transfer(Object { id: id2, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let obj = test_scenario::take_one<Object>(&mut scenario);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    fun test_events_of_type_in_window() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy other);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+            event::emit(TestEvent { value: 1 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+
+        let transfers = test_scenario::events_of_type_in_window(0, begin, end);
+        assert!(vector::length(&transfers) == 1, 0);
+        let info = vector::borrow(&transfers, 0);
+        assert!(test_scenario::event_info_recipient(info) == x"0000000000000000000000000000000000000001", 1);
+        assert!(test_scenario::event_info_object_id(info) == option::some(obj_id), ID_BYTES_MISMATCH);
+
+        let user_events = test_scenario::events_of_type_in_window(6, begin, end);
+        assert!(vector::length(&user_events) == 1, 2);
+        assert!(option::is_none(&test_scenario::event_info_object_id(vector::borrow(&user_events, 0))), 3);
+        assert!(vector::is_empty(&test_scenario::events_of_type_in_window(0, end, end)), 4);
+    }
+
+    #[test]
+    fun test_assert_no_objects_leaked() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            let Object { id, value: _ } = obj;
+            id::delete(id);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_no_objects_leaked(0, begin);
+        test_scenario::assert_no_objects_leaked(begin, test_scenario::last_tx_index());
+    }
+
+    #[test]
+    fun test_created_object_ids() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let old_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let new_id = *id::inner(&versioned_id);
+        {
+            let old = test_scenario::take_owned_by_id<Object>(&mut scenario, old_id);
+            transfer::transfer(old, copy other);
+            transfer::transfer(Object { id: versioned_id, value: 20 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::created_object_ids(begin) == vector::singleton(new_id), ID_BYTES_MISMATCH);
+        assert!(vector::length(&test_scenario::created_object_ids(0)) == 2, 0);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 109 /* EEVENT_INDEX_OUT_OF_BOUNDS */)]
+    fun test_created_object_ids_out_of_bounds() {
+        test_scenario::created_object_ids(test_scenario::last_tx_index() + 1);
+    }
+
+    #[test]
+    fun test_drop_from_inventory() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        test_scenario::drop_from_inventory(obj_id);
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), OBJECT_ID_NOT_FOUND);
+        assert!(vector::is_empty(&test_scenario::typed_deleted_object_ids<Object>(begin)), 0);
+    }
+
+    #[test]
+    fun test_total_distinct_objects() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            transfer::transfer(obj, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::total_distinct_objects(begin, end) == 2, 0);
+        assert!(test_scenario::total_distinct_objects(end, end) == 0, 1);
+    }
+
+    #[test]
+    fun test_inventory_by_owner() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, @0x2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 30 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 40 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let inventory = test_scenario::inventory_by_owner(&scenario);
+        assert!(vector::length(&inventory) == 2, 0);
+        let first = vector::borrow(&inventory, 0);
+        assert!(test_scenario::owner_objects_owner(first) == x"0000000000000000000000000000000000000001", 1);
+        assert!(vector::length(&test_scenario::owner_objects_ids(first)) == 2, 2);
+        let second = vector::borrow(&inventory, 1);
+        assert!(test_scenario::owner_objects_owner(second) == x"0000000000000000000000000000000000000002", 3);
+        assert!(test_scenario::owner_objects_ids(second) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    fun test_owner_kind_of() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let owned_versioned_id = test_scenario::new_id(&mut scenario);
+        let owned_id = *id::inner(&owned_versioned_id);
+        let shared_versioned_id = test_scenario::new_id(&mut scenario);
+        let shared_id = *id::inner(&shared_versioned_id);
+        let deleted_versioned_id = test_scenario::new_id(&mut scenario);
+        let deleted_id = *id::inner(&deleted_versioned_id);
+        {
+            transfer::transfer(Object { id: owned_versioned_id, value: 10 }, copy sender);
+            transfer::share_object(Object { id: shared_versioned_id, value: 20 });
+            id::delete(deleted_versioned_id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::owner_kind_of(&scenario, owned_id) == 0, 0);
+        assert!(test_scenario::owner_kind_of(&scenario, shared_id) == 3, 1);
+        assert!(test_scenario::owner_kind_of(&scenario, deleted_id) == 255, 2);
+    }
+
+    #[test]
+    fun test_mark_shared_object_locked_released_by_next_tx() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::mark_shared_object_locked(obj_id);
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let wrapper = test_scenario::take_shared_by_id<Object>(&mut scenario, obj_id);
+            test_scenario::return_shared(&mut scenario, wrapper);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 115 /* ESHARED_OBJECT_LOCKED */)]
+    fun test_take_locked_shared_object() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::mark_shared_object_locked(obj_id);
+        let wrapper = test_scenario::take_shared_by_id<Object>(&mut scenario, obj_id);
+        test_scenario::return_shared(&mut scenario, wrapper);
+    }
+
+    #[test]
+    fun test_all_ids_of_type() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+            // wrapped objects are not live
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 30 };
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child }, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let (ids, owners) = test_scenario::all_ids_of_type<Object>(&scenario);
+        assert!(vector::length(&ids) == 2, 0);
+        assert!(vector::length(&owners) == 2, 1);
+        let shared = 0;
+        let i = 0;
+        while (i < vector::length(&owners)) {
+            if (test_scenario::owner_info_kind(vector::borrow(&owners, i)) == 3) {
+                shared = shared + 1;
+            };
+            i = i + 1;
+        };
+        assert!(shared == 1, 2);
+    }
+
+    #[test]
+    fun test_delete_anomalies() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        test_scenario::set_strict_delete_checking(true);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+            // an ID never used by an object is not an anomaly
+            id::delete(test_scenario::new_id(&mut scenario));
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(vector::is_empty(&test_scenario::delete_anomalies(&scenario)), 0);
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            test_scenario::drop_from_inventory(obj_id);
+            let Object { id, value: _ } = obj;
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::delete_anomalies(&scenario) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    fun test_take_owned_at_index() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let i = 0;
+            while (i < 3) {
+                let id = test_scenario::new_id(&mut scenario);
+                transfer::transfer(Object { id, value: i }, copy sender);
+                i = i + 1;
+            };
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let second = test_scenario::take_owned_at_index<Object>(&mut scenario, 1);
+            assert!(second.value == 1, VALUE_MISMATCH);
+            let third = test_scenario::take_owned_at_index<Object>(&mut scenario, 2);
+            assert!(third.value == 2, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, second);
+            test_scenario::return_owned(&mut scenario, third);
+        };
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 116 /* EOBJECT_INDEX_OUT_OF_RANGE */)]
+    fun test_take_owned_at_index_out_of_range() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let obj = test_scenario::take_owned_at_index<Object>(&mut scenario, 1);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    fun test_freeze_object_for_testing() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            test_scenario::freeze_object_for_testing(Object { id: versioned_id, value: 10 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::frozen_ids(&scenario) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+        {
+            let wrapper = test_scenario::take_immutable_by_id<Object>(&mut scenario, obj_id);
+            assert!(test_scenario::borrow(&wrapper).value == 10, VALUE_MISMATCH);
+            test_scenario::return_immutable(&mut scenario, wrapper);
+        };
+    }
+
+    #[test]
+    fun test_inventory_diff() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let moved_versioned_id = test_scenario::new_id(&mut scenario);
+        let moved_id = *id::inner(&moved_versioned_id);
+        let removed_versioned_id = test_scenario::new_id(&mut scenario);
+        let removed_id = *id::inner(&removed_versioned_id);
+        {
+            transfer::transfer(Object { id: moved_versioned_id, value: 10 }, copy sender);
+            transfer::transfer(Object { id: removed_versioned_id, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let before = test_scenario::last_tx_index();
+        let created_versioned_id = test_scenario::new_id(&mut scenario);
+        let created_id = *id::inner(&created_versioned_id);
+        {
+            let moved = test_scenario::take_owned_by_id<Object>(&mut scenario, moved_id);
+            transfer::transfer(moved, copy other);
+            let removed = test_scenario::take_owned_by_id<Object>(&mut scenario, removed_id);
+            test_scenario::delete_object_for_testing(removed);
+            transfer::transfer(Object { id: created_versioned_id, value: 30 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let diff = test_scenario::inventory_diff(before, test_scenario::last_tx_index());
+        assert!(test_scenario::inventory_diff_created(&diff) == vector::singleton(id::bytes(&created_id)), 0);
+        assert!(test_scenario::inventory_diff_removed(&diff) == vector::singleton(id::bytes(&removed_id)), 1);
+        assert!(test_scenario::inventory_diff_moved(&diff) == vector::singleton(id::bytes(&moved_id)), 2);
+    }
+
+    #[test]
+    fun test_assert_type_tag() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let type_name = test_scenario::type_of_object(&scenario, obj_id);
+        test_scenario::assert_type_tag(&scenario, obj_id, *utf8::bytes(&type_name));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 117 /* ETYPE_TAG_MISMATCH */)]
+    fun test_assert_type_tag_mismatch() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_type_tag(&scenario, obj_id, b"0x2::test_scenarioTests::Wrapper");
+    }
+
+    #[test]
+    fun test_num_events_since_reset() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_events_since_reset() == test_scenario::last_tx_index(), 0);
+        test_scenario::reset(&mut scenario);
+        assert!(test_scenario::num_events_since_reset() == 0, 1);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, copy sender);
+        };
+        assert!(test_scenario::num_events_since_reset() == 1, 2);
+    }
+
+    #[test]
+    fun test_object_bytes() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let bytes = test_scenario::object_bytes(&scenario, obj_id);
+        assert!(vector::length(&bytes) == test_scenario::object_size(&scenario, obj_id), 0);
+        // the serialization starts with the ID of the object
+        let id_bytes = id::bytes(&obj_id);
+        let i = 0;
+        while (i < vector::length(&id_bytes)) {
+            assert!(*vector::borrow(&bytes, i) == *vector::borrow(&id_bytes, i), ID_BYTES_MISMATCH);
+            i = i + 1;
+        };
+    }
+
+    #[test]
+    fun test_objects_equal() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let id1 = test_scenario::new_id(&mut scenario);
+        let obj_id1 = *id::inner(&id1);
+        let id2 = test_scenario::new_id(&mut scenario);
+        let obj_id2 = *id::inner(&id2);
+        let id3 = test_scenario::new_id(&mut scenario);
+        let obj_id3 = *id::inner(&id3);
+        {
+            transfer::transfer(Object { id: id1, value: 10 }, copy sender);
+            transfer::transfer(Object { id: id2, value: 10 }, copy sender);
+            transfer::transfer(Object { id: id3, value: 20 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::objects_equal(&scenario, obj_id1, obj_id1), 0);
+        // the serializations start with the IDs, which differ
+        assert!(!test_scenario::objects_equal(&scenario, obj_id1, obj_id2), 1);
+        assert!(test_scenario::objects_equal_ignoring_id(&scenario, obj_id1, obj_id2), 2);
+        assert!(!test_scenario::objects_equal_ignoring_id(&scenario, obj_id1, obj_id3), 3);
+    }
+
+    #[test]
+    fun test_owner_address_of() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let owned_versioned_id = test_scenario::new_id(&mut scenario);
+        let owned_id = *id::inner(&owned_versioned_id);
+        let child_versioned_id = test_scenario::new_id(&mut scenario);
+        let child_id = *id::inner(&child_versioned_id);
+        let shared_versioned_id = test_scenario::new_id(&mut scenario);
+        let shared_id = *id::inner(&shared_versioned_id);
+        {
+            transfer::transfer(Object { id: owned_versioned_id, value: 10 }, @0x1);
+            let child = Object { id: child_versioned_id, value: 20 };
+            test_scenario::transfer_to_object_for_testing(child, owned_id);
+            transfer::share_object(Object { id: shared_versioned_id, value: 30 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::owner_address_of(&scenario, owned_id) == x"0000000000000000000000000000000000000001", 0);
+        assert!(test_scenario::owner_address_of(&scenario, child_id) == id::bytes(&owned_id), 1);
+        assert!(vector::is_empty(&test_scenario::owner_address_of(&scenario, shared_id)), 2);
+    }
+
+    #[test]
+    fun test_freeze_after_transfer() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            transfer::freeze_object(obj);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        // the later freeze overrides the earlier transfer: the object is immutable, and no longer
+        // in the inventory of its previous owner
+        assert!(test_scenario::owner_kind_of(&scenario, obj_id) == 2, 0);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), 1);
+        assert!(test_scenario::frozen_ids(&scenario) == vector::singleton(obj_id), ID_BYTES_MISMATCH);
+    }
+
+    #[test]
+    fun test_object_created_at() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        let unused_id = test_scenario::new_id(&mut scenario);
+        {
+            transfer::share_object(Object { id: versioned_id, value: 20 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::object_created_at(obj_id) == begin, 0);
+        assert!(test_scenario::object_created_at(*id::inner(&unused_id)) == 18446744073709551615, 1);
+        id::delete(unused_id);
+    }
+
+    #[test]
+    fun test_object_last_touched_at() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let created = test_scenario::object_created_at(obj_id);
+        assert!(test_scenario::object_last_touched_at(obj_id) == created, 0);
+        let begin = test_scenario::last_tx_index();
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, copy sender);
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            transfer::transfer(obj, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::object_last_touched_at(obj_id) == begin + 1, 1);
+    }
+
+    #[test]
+    fun test_take_from_sender() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &other);
+        {
+            let obj = test_scenario::take_from_sender<Object>(&mut scenario);
+            assert!(obj.value == 10, VALUE_MISMATCH);
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+    }
+
+    #[test]
+    fun test_ids_owned_by_any() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, @0x1);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, @0x2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 30 }, @0x3);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let owners = vector[
+            x"0000000000000000000000000000000000000001",
+            x"0000000000000000000000000000000000000002",
+            x"0000000000000000000000000000000000000002",
+        ];
+        assert!(vector::length(&test_scenario::ids_owned_by_any<Object>(&scenario, owners)) == 2, 0);
+        let all_owners = test_scenario::distinct_owners(&scenario);
+        assert!(vector::length(&test_scenario::ids_owned_by_any<Object>(&scenario, all_owners)) == 3, 1);
+    }
+
+    #[test]
+    fun test_transfer_count_for() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let obj_id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let setup_end = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            transfer::transfer(obj, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &other);
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj_id);
+            transfer::freeze_object(obj);
+        };
+        test_scenario::next_tx(&mut scenario, &other);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::transfer_count_for(obj_id, begin, setup_end) == 1, 0);
+        assert!(test_scenario::transfer_count_for(obj_id, setup_end, end) == 1, 1);
+        assert!(test_scenario::transfer_count_for(obj_id, begin, end) == 2, 2);
+    }
+
+    #[test]
+    fun test_validate_event_log() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 20 });
+            event::emit(TestEvent { value: 1 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::reset(&mut scenario);
+        assert!(vector::is_empty(&test_scenario::validate_event_log()), 0);
+    }
+
+    #[test]
+    fun test_sum_u64_field() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 20 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 40 }, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::sum_u64_field<Object>(&scenario, sender, vector[1]) == 30, 0);
+        // the version of the objects, nested in their `VersionedID`
+        assert!(test_scenario::sum_u64_field<Object>(&scenario, @0x1, vector[0, 1]) == 0, 1);
+        assert!(test_scenario::sum_u64_field<Wrapper>(&scenario, sender, vector[1]) == 0, 2);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 119 /* EINVALID_FIELD_PATH */)]
+    fun test_sum_u64_field_invalid_path() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::sum_u64_field<Object>(&scenario, sender, vector[0]);
+    }
+
+    #[test]
+    fun test_clear_owner_inventory() {
+        let sender = @0x0;
+        let other = @0x1;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let versioned_id3 = test_scenario::new_id(&mut scenario);
+        let id1 = *id::inner(&versioned_id1);
+        let id2 = *id::inner(&versioned_id2);
+        let id3 = *id::inner(&versioned_id3);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 1 }, copy sender);
+            transfer::transfer(Object { id: versioned_id2, value: 2 }, copy sender);
+            transfer::transfer(Object { id: versioned_id3, value: 3 }, copy other);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let tx_begin_idx = test_scenario::last_tx_index();
+        test_scenario::clear_owner_inventory(&scenario, sender);
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), 0);
+        let deleted = test_scenario::typed_deleted_object_ids<Object>(tx_begin_idx);
+        assert!(vector::length(&deleted) == 2, 1);
+        assert!(vector::contains(&deleted, &id1), 2);
+        assert!(vector::contains(&deleted, &id2), 3);
+        test_scenario::next_tx(&mut scenario, &other);
+        let obj = test_scenario::take_owned<Object>(&mut scenario);
+        assert!(id::id(&obj) == &id3, 4);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    fun test_snapshot_and_restore_inventory() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::snapshot_inventory(&mut scenario, b"start");
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 20 };
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), 0);
+        test_scenario::restore_inventory(&mut scenario, b"start");
+        assert!(!test_scenario::can_take_owned<Wrapper>(&scenario), 1);
+        let obj = test_scenario::take_owned<Object>(&mut scenario);
+        assert!(id::id(&obj) == &id, 2);
+        test_scenario::delete_object_for_testing(obj);
+        test_scenario::next_tx(&mut scenario, &sender);
+        // a snapshot can be restored several times
+        test_scenario::restore_inventory(&mut scenario, b"start");
+        assert!(test_scenario::can_take_owned<Object>(&scenario), 3);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 9 /* ESnapshotNotFound */)]
+    fun test_restore_unknown_inventory_snapshot() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        test_scenario::snapshot_inventory(&mut scenario, b"start");
+        test_scenario::restore_inventory(&mut scenario, b"other");
+    }
+
+    #[test]
+    fun test_is_shared_object() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let shared_id = test_scenario::new_id(&mut scenario);
+        let owned_id = test_scenario::new_id(&mut scenario);
+        let shared = *id::inner(&shared_id);
+        let owned = *id::inner(&owned_id);
+        {
+            transfer::share_object(Object { id: shared_id, value: 1 });
+            transfer::transfer(Object { id: owned_id, value: 2 }, copy sender);
+        };
+        // objects are only in the inventory from the next transaction on
+        assert!(!test_scenario::is_shared_object(&scenario, shared), 0);
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::is_shared_object(&scenario, shared), 1);
+        assert!(!test_scenario::is_shared_object(&scenario, owned), 2);
+    }
+
+    #[test]
+    fun test_object_layout() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let id1 = test_scenario::new_id(&mut scenario);
+        let id2 = test_scenario::new_id(&mut scenario);
+        let id3 = test_scenario::new_id(&mut scenario);
+        let obj1 = *id::inner(&id1);
+        let obj2 = *id::inner(&id2);
+        let wrapper = *id::inner(&id3);
+        {
+            transfer::transfer(Object { id: id1, value: 1 }, copy sender);
+            transfer::transfer(Object { id: id2, value: 2 }, copy sender);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 3 };
+            transfer::transfer(Wrapper { id: id3, child }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let layout = test_scenario::object_layout(&scenario, obj1);
+        assert!(!vector::is_empty(&layout), 0);
+        // the layout depends on the type of the object, not on its contents
+        assert!(layout == test_scenario::object_layout(&scenario, obj2), 1);
+        assert!(layout != test_scenario::object_layout(&scenario, wrapper), 2);
+    }
+
+    #[test]
+    fun test_assert_inventory_empty() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        test_scenario::assert_inventory_empty(&scenario, sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_inventory_empty(&scenario, sender);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 120 /* EINVENTORY_NOT_EMPTY */)]
+    fun test_assert_inventory_empty_fails() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_inventory_empty(&scenario, sender);
+    }
+
+    #[test]
+    fun test_ids_moved_between() {
+        let alice = @0xA;
+        let bob = @0xB;
+        let carol = @0xC;
+        let scenario = test_scenario::begin(&alice);
+        let id1 = test_scenario::new_id(&mut scenario);
+        let id2 = test_scenario::new_id(&mut scenario);
+        let id3 = test_scenario::new_id(&mut scenario);
+        let obj1 = *id::inner(&id1);
+        let obj2 = *id::inner(&id2);
+        {
+            transfer::transfer(Object { id: id1, value: 1 }, copy alice);
+            transfer::transfer(Object { id: id2, value: 2 }, copy alice);
+            transfer::transfer(Object { id: id3, value: 3 }, copy alice);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        let before = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj1);
+            transfer::transfer(obj, copy bob);
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, obj2);
+            transfer::transfer(obj, copy carol);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        let after = test_scenario::last_tx_index();
+        let moved = test_scenario::ids_moved_between(alice, bob, before, after);
+        assert!(moved == vector[id::bytes(&obj1)], 0);
+        assert!(vector::is_empty(&test_scenario::ids_moved_between(bob, alice, before, after)), 1);
+        assert!(vector::is_empty(&test_scenario::ids_moved_between(alice, bob, before, before)), 2);
+    }
+
+    #[test]
+    fun test_num_shared_objects() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        assert!(test_scenario::num_shared_objects(&scenario) == 0, 0);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 1 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 2 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 3 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 4 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_shared_objects(&scenario) == 2, 1);
+    }
+
+    #[test]
+    fun test_ids_of_any_type() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let object_id = test_scenario::new_id(&mut scenario);
+        let wrapper_id = test_scenario::new_id(&mut scenario);
+        let obj = *id::inner(&object_id);
+        let wrapper = *id::inner(&wrapper_id);
+        {
+            transfer::transfer(Object { id: object_id, value: 1 }, copy sender);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 2 };
+            transfer::transfer(Wrapper { id: wrapper_id, child }, @0x1);
+            create_parent_and_object(&mut scenario);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let (type_indices, ids) = test_scenario::ids_of_any_type<Wrapper, Object>(&scenario);
+        // the object owned by the parent created by `create_parent_and_object` is an `Object` too
+        assert!(vector::length(&ids) == 3, 0);
+        assert!(vector::length(&type_indices) == 3, 1);
+        let (found, i) = vector::index_of(&ids, &wrapper);
+        assert!(found && *vector::borrow(&type_indices, i) == 0, 2);
+        let (found, i) = vector::index_of(&ids, &obj);
+        assert!(found && *vector::borrow(&type_indices, i) == 1, 3);
+
+        // the parent created by `create_parent_and_object` is the only `Parent`
+        let (type_indices, ids) = test_scenario::ids_of_any_type_3<Wrapper, Object, Parent>(&scenario);
+        assert!(vector::length(&ids) == 4, 4);
+        let (found, i) = vector::index_of(&ids, &wrapper);
+        assert!(found && *vector::borrow(&type_indices, i) == 0, 5);
+        let (found, _) = vector::index_of(&type_indices, &2);
+        assert!(found, 6);
+    }
+
+    #[test]
+    fun test_replace_object() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        // an `Object` is serialized as its ID and version followed by its value
+        let object_bytes = test_scenario::object_bytes(&scenario, id);
+        let value_start = vector::length(&object_bytes) - 8;
+        let new_bytes = vector::empty();
+        let i = 0;
+        while (i < value_start) {
+            vector::push_back(&mut new_bytes, *vector::borrow(&object_bytes, i));
+            i = i + 1;
+        };
+        vector::append(&mut new_bytes, bcs::to_bytes(&42u64));
+        test_scenario::replace_object<Object>(&scenario, id, new_bytes);
+        test_scenario::next_tx(&mut scenario, &sender);
+        let obj = test_scenario::take_owned<Object>(&mut scenario);
+        assert!(obj.value == 42, VALUE_MISMATCH);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 121 /* EINVALID_OBJECT_BYTES */)]
+    fun test_replace_object_invalid_bytes() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 10 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::replace_object<Object>(&scenario, id, vector[1, 2, 3]);
+    }
+
+    #[test]
+    fun test_all_seen_object_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let id1 = *id::inner(&versioned_id1);
+        let id2 = *id::inner(&versioned_id2);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 1 }, copy sender);
+            transfer::transfer(Object { id: versioned_id2, value: 2 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let Object { id, value: _ } = test_scenario::take_owned_by_id<Object>(&mut scenario, id1);
+            id::delete(id);
+            event::emit(TestEvent { value: 1 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::all_seen_object_ids(begin, end) == vector[id::bytes(&id1)], 0);
+        let seen = test_scenario::all_seen_object_ids(0, end);
+        assert!(vector::length(&seen) == 2, 1);
+        assert!(vector::contains(&seen, &id::bytes(&id2)), 2);
+    }
+
+    #[test]
+    fun test_event_histogram() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::freeze_object(Object { id, value: 2 });
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 3 });
+            // a transfer to an object and a transfer to an address
+            create_parent_and_object(&mut scenario);
+            event::emit(TestEvent { value: 1 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::event_histogram(begin, end) == vector[2, 1, 1, 1, 0, 1], 0);
+        assert!(test_scenario::event_histogram(end, end) == vector[0, 0, 0, 0, 0, 0], 1);
+    }
+
+    #[test]
+    fun test_ever_owned() {
+        let alice = @0xA;
+        let bob = @0xB;
+        let carol = @0xC;
+        let scenario = test_scenario::begin(&alice);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 1 }, copy alice);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        let begin = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            transfer::transfer(obj, copy bob);
+        };
+        test_scenario::next_tx(&mut scenario, &bob);
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            transfer::transfer(obj, copy carol);
+        };
+        test_scenario::next_tx(&mut scenario, &carol);
+        let end = test_scenario::last_tx_index();
+        // alice owned the object at the start of the window
+        assert!(test_scenario::ever_owned(alice, id, begin, end), 0);
+        assert!(test_scenario::ever_owned(bob, id, begin, end), 1);
+        assert!(test_scenario::ever_owned(carol, id, begin, end), 2);
+        assert!(!test_scenario::ever_owned(@0xD, id, begin, end), 3);
+        assert!(!test_scenario::ever_owned(alice, id, end, end), 4);
+    }
+
+    #[test]
+    fun test_assert_no_ownership_changes() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            obj.value = 2;
+            test_scenario::return_owned(&mut scenario, obj);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_no_ownership_changes(begin, test_scenario::last_tx_index());
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 122 /* ELIVE_OBJECTS_CHANGED */)]
+    fun test_assert_no_ownership_changes_created() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_no_ownership_changes(begin, test_scenario::last_tx_index());
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 123 /* EOBJECT_OWNER_CHANGED */)]
+    fun test_assert_no_ownership_changes_moved() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned<Object>(&mut scenario);
+            transfer::transfer(obj, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_no_ownership_changes(begin, test_scenario::last_tx_index());
+    }
+
+    #[test]
+    fun test_ids_sorted_by_u64_field() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let versioned_id3 = test_scenario::new_id(&mut scenario);
+        let id1 = *id::inner(&versioned_id1);
+        let id2 = *id::inner(&versioned_id2);
+        let id3 = *id::inner(&versioned_id3);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 5 }, copy sender);
+            transfer::transfer(Object { id: versioned_id2, value: 30 }, copy sender);
+            transfer::transfer(Object { id: versioned_id3, value: 20 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 100 }, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let ids = test_scenario::ids_sorted_by_u64_field<Object>(&scenario, sender, vector[1]);
+        assert!(ids == vector[id2, id3, id1], 0);
+    }
+
+    #[test]
+    fun test_parent_object_of() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let parent_versioned_id = test_scenario::new_id(&mut scenario);
+        let parent_id = *id::inner(&parent_versioned_id);
+        let child_versioned_id = test_scenario::new_id(&mut scenario);
+        let child_id = *id::inner(&child_versioned_id);
+        {
+            let child = Object { id: child_versioned_id, value: 10 };
+            transfer::transfer(Object { id: parent_versioned_id, value: 20 }, copy sender);
+            test_scenario::transfer_to_object_for_testing(child, parent_id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let parent = test_scenario::parent_object_of(&scenario, child_id);
+        assert!(parent == option::some(parent_id), ID_BYTES_MISMATCH);
+        assert!(option::is_none(&test_scenario::parent_object_of(&scenario, parent_id)), 0);
+    }
+
+    #[test]
+    fun test_emit_user_event_for_testing() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        test_scenario::emit_user_event_for_testing(7, b"checkpoint");
+        test_scenario::emit_user_event_for_testing(7, vector::empty());
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::event_histogram(begin, end) == vector[0, 0, 0, 0, 0, 2], 0);
+        assert!(vector::is_empty(&test_scenario::validate_event_log()), 1);
+        assert!(!test_scenario::can_take_owned<Object>(&scenario), 2);
+    }
+
+    #[test]
+    fun test_user_events_with_tag() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let begin = test_scenario::last_tx_index();
+        test_scenario::emit_user_event_for_testing(1, b"first");
+        test_scenario::emit_user_event_for_testing(2, b"other");
+        event::emit(TestEvent { value: 1 });
+        test_scenario::emit_user_event_for_testing(1, b"second");
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        assert!(test_scenario::user_events_with_tag(1, begin, end) == vector[b"first", b"second"], 0);
+        assert!(test_scenario::user_events_with_tag(2, begin, end) == vector[b"other"], 1);
+        assert!(vector::is_empty(&test_scenario::user_events_with_tag(3, begin, end)), 2);
+    }
+
+    #[test]
+    fun test_assert_same_type() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let id1 = *id::inner(&versioned_id1);
+        let id2 = *id::inner(&versioned_id2);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 1 }, copy sender);
+            transfer::share_object(Object { id: versioned_id2, value: 2 });
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_same_type(&scenario, id1, id2);
+        test_scenario::assert_same_type(&scenario, id1, id1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 103 /* ETYPE_MISMATCH */)]
+    fun test_assert_same_type_mismatch() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let object_id = test_scenario::new_id(&mut scenario);
+        let wrapper_id = test_scenario::new_id(&mut scenario);
+        let obj = *id::inner(&object_id);
+        let wrapper = *id::inner(&wrapper_id);
+        {
+            transfer::transfer(Object { id: object_id, value: 1 }, copy sender);
+            let child = Object { id: test_scenario::new_id(&mut scenario), value: 2 };
+            transfer::transfer(Wrapper { id: wrapper_id, child }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_same_type(&scenario, obj, wrapper);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 102 /* EOBJECT_NOT_FOUND */)]
+    fun test_assert_same_type_not_found() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        let missing_id = test_scenario::new_id(&mut scenario);
+        let missing = *id::inner(&missing_id);
+        id::delete(missing_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        test_scenario::assert_same_type(&scenario, id, missing);
+    }
+
+    #[test]
+    fun test_num_live_objects() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        assert!(test_scenario::num_live_objects(&scenario) == 0, 0);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 1 }, copy sender);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::share_object(Object { id, value: 2 });
+            // a parent and its child
+            create_parent_and_object(&mut scenario);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_live_objects(&scenario) == 4, 1);
+        {
+            let Object { id, value: _ } = test_scenario::take_owned<Object>(&mut scenario);
+            id::delete(id);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        assert!(test_scenario::num_live_objects(&scenario) == 3, 2);
+    }
+
+    #[test]
+    fun test_dangling_wrapped_ids() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id1 = test_scenario::new_id(&mut scenario);
+        let versioned_id2 = test_scenario::new_id(&mut scenario);
+        let id1 = *id::inner(&versioned_id1);
+        let id2 = *id::inner(&versioned_id2);
+        {
+            transfer::transfer(Object { id: versioned_id1, value: 1 }, copy sender);
+            transfer::transfer(Object { id: versioned_id2, value: 2 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let begin = test_scenario::last_tx_index();
+        let wrapper_versioned_id = test_scenario::new_id(&mut scenario);
+        let wrapper_id = *id::inner(&wrapper_versioned_id);
+        {
+            let obj1 = test_scenario::take_owned_by_id<Object>(&mut scenario, id1);
+            transfer::transfer(Wrapper { id: wrapper_versioned_id, child: obj1 }, copy sender);
+            let obj2 = test_scenario::take_owned_by_id<Object>(&mut scenario, id2);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Wrapper { id, child: obj2 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let end = test_scenario::last_tx_index();
+        let dangling = test_scenario::dangling_wrapped_ids(begin, end);
+        assert!(vector::length(&dangling) == 2, 0);
+        {
+            // unwrap the first object
+            let wrapper = test_scenario::take_owned_by_id<Wrapper>(&mut scenario, wrapper_id);
+            let Wrapper { id, child } = wrapper;
+            id::delete(id);
+            transfer::transfer(child, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let dangling = test_scenario::dangling_wrapped_ids(begin, test_scenario::last_tx_index());
+        assert!(dangling == vector[id::bytes(&id2)], 1);
+    }
+
+    #[test]
+    fun test_take_by_id_at_version() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 1 }, @0x1);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let version = test_scenario::object_version(&scenario, id);
+        let obj = test_scenario::take_by_id_at_version<Object>(&mut scenario, id, version);
+        assert!(obj.value == 1, VALUE_MISMATCH);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 124 /* EVERSION_MISMATCH */)]
+    fun test_take_by_id_at_version_mismatch() {
+        let sender = @0x0;
+        let scenario = test_scenario::begin(&sender);
+        let versioned_id = test_scenario::new_id(&mut scenario);
+        let id = *id::inner(&versioned_id);
+        {
+            transfer::transfer(Object { id: versioned_id, value: 1 }, copy sender);
+        };
+        test_scenario::next_tx(&mut scenario, &sender);
+        let version = test_scenario::object_version(&scenario, id);
+        let obj = test_scenario::take_by_id_at_version<Object>(&mut scenario, id, version + 1);
+        test_scenario::return_owned(&mut scenario, obj);
+    }
+
+    #[test]
+    fun test_owned_count_delta() {
+        let alice = @0xA;
+        let bob = @0xB;
+        let scenario = test_scenario::begin(&alice);
+        let begin = test_scenario::last_tx_index();
+        let id = test_scenario::new_id(&mut scenario);
+        let moved_id = *id::inner(&id);
+        transfer::transfer(Object { id, value: 1 }, copy alice);
+        {
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 2 }, copy alice);
+            let id = test_scenario::new_id(&mut scenario);
+            transfer::transfer(Object { id, value: 3 }, copy alice);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        let middle = test_scenario::last_tx_index();
+        {
+            let obj = test_scenario::take_owned_by_id<Object>(&mut scenario, moved_id);
+            transfer::transfer(obj, copy bob);
+        };
+        test_scenario::next_tx(&mut scenario, &alice);
+        let end = test_scenario::last_tx_index();
+        let (magnitude, is_negative) = test_scenario::owned_count_delta(alice, begin, middle);
+        assert!(magnitude == 3 && !is_negative, 0);
+        let (magnitude, is_negative) = test_scenario::owned_count_delta(alice, middle, end);
+        assert!(magnitude == 1 && is_negative, 1);
+        let (magnitude, is_negative) = test_scenario::owned_count_delta(bob, middle, end);
+        assert!(magnitude == 1 && !is_negative, 2);
+        let (magnitude, is_negative) = test_scenario::owned_count_delta(bob, end, end);
+        assert!(magnitude == 0 && !is_negative, 3);
+    }
+
     /// Create object and parent. object is a child of parent.
     /// parent is owned by sender of `scenario`.
     fun create_parent_and_object(scenario: &mut Scenario) {